@@ -59,7 +59,7 @@ fn process_input_file(filename: &str) -> Vec<Vec<u64>> {
 
 /// Solves AOC 2022 Day 1 Part 1 // Returns the maximum total calories across each of the elf packs.
 fn solve_part1(elf_packs: &[Vec<u64>]) -> u64 {
-    return elf_packs.iter().map(|x| x.iter().sum()).max().unwrap();
+    elf_packs.iter().map(|x| x.iter().sum()).max().unwrap()
 }
 
 /// Solves AOC 2022 Day 1 Part 2 // Returns the total calories for the elf packs with the top three
@@ -70,7 +70,7 @@ fn solve_part2(elf_packs: &[Vec<u64>]) -> u64 {
         .map(|x| x.iter().sum())
         .collect::<Vec<u64>>();
     sums.sort();
-    return sums.iter().rev().take(3).sum();
+    sums.iter().rev().take(3).sum()
 }
 
 #[cfg(test)]
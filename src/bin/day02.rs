@@ -44,11 +44,11 @@ fn process_input_file(filename: &str) -> Vec<String> {
     // Read contents of problem input file
     let raw_input = fs::read_to_string(filename).unwrap();
     // Process input file contents into data structure
-    return raw_input
+    raw_input
         .trim()
         .lines()
         .map(|line| String::from(line.trim()))
-        .collect::<Vec<String>>();
+        .collect::<Vec<String>>()
 }
 
 /// Solves AOC 2022 Day 2 Part 1 // Returns the total score from playing the "rock paper scissors"
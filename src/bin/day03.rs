@@ -1,4 +1,3 @@
-use std::collections::HashSet;
 use std::fs;
 use std::time::Instant;
 
@@ -6,20 +5,22 @@ const PROBLEM_NAME: &str = "Rucksack Reorganization";
 const PROBLEM_INPUT_FILE: &str = "./input/day03.txt";
 const PROBLEM_DAY: u64 = 3;
 
+const GROUP_SIZE: usize = 3;
+
 /// Processes the AOC 2022 Day 3 input file and solves both parts of the problem. Solutions are
 /// printed to stdout.
 pub fn main() {
     let start = Instant::now();
     // Input processing
-    let input = process_input_file(PROBLEM_INPUT_FILE);
+    let input = process_input_file(PROBLEM_INPUT_FILE).unwrap();
     let input_parser_timestamp = Instant::now();
     let input_parser_duration = input_parser_timestamp.duration_since(start);
     // Solve part 1
-    let p1_solution = solve_part1(&input);
+    let p1_solution = solve_part1(&input).unwrap();
     let p1_timestamp = Instant::now();
     let p1_duration = p1_timestamp.duration_since(input_parser_timestamp);
     // Solve part 2
-    let p2_solution = solve_part2(&input);
+    let p2_solution = solve_part2(&input).unwrap();
     let p2_timestamp = Instant::now();
     let p2_duration = p2_timestamp.duration_since(p1_timestamp);
     // Print results
@@ -40,58 +41,165 @@ pub fn main() {
 }
 
 /// Processes the AOC 2022 Day 3 input file in the format required by the solver functions.
-/// Returned value is vector or strings extracted from the lines of the input file.
-fn process_input_file(filename: &str) -> Vec<String> {
+/// Returned value is vector of rucksacks extracted from the lines of the input file, or an error
+/// if any line is not a valid rucksack.
+fn process_input_file(filename: &str) -> Result<Vec<Rucksack>, String> {
     // Read contents of problem input file
     let raw_input = fs::read_to_string(filename).unwrap();
     // Process input file contents into data structure
-    return raw_input
-        .trim()
-        .lines()
-        .map(|line| String::from(line.trim()))
-        .collect::<Vec<String>>();
+    parse_rucksacks(&raw_input)
+}
+
+/// Parses the rucksacks from the lines of the raw input. Each line must contain an even number of
+/// valid items (a-z and A-Z) so it can be split into two equal compartments.
+fn parse_rucksacks(raw_input: &str) -> Result<Vec<Rucksack>, String> {
+    let mut rucksacks: Vec<Rucksack> = vec![];
+    for (i, line) in raw_input.trim().lines().enumerate() {
+        let line = line.trim();
+        // Items are ASCII letters, so check for other characters before splitting by length
+        if !line.is_ascii() {
+            return Err(format!(
+                "Day 3 - line {} contains non-ASCII characters",
+                i + 1
+            ));
+        }
+        if line.len() % 2 != 0 {
+            return Err(format!(
+                "Day 3 - line {} has odd length {} and cannot be split into compartments",
+                i + 1,
+                line.len()
+            ));
+        }
+        let (first, second) = line.split_at(line.len() / 2);
+        let first = ItemSet::from_items(first).map_err(|e| format!("line {}: {}", i + 1, e))?;
+        let second = ItemSet::from_items(second).map_err(|e| format!("line {}: {}", i + 1, e))?;
+        rucksacks.push(Rucksack { first, second });
+    }
+    Ok(rucksacks)
 }
 
 /// Solves AOC 2022 Day 3 Part 1 // Returns the total prioritisation of the items in both
 /// compartments for all knapsacks.
-fn solve_part1(input: &[String]) -> u64 {
+fn solve_part1(input: &[Rucksack]) -> Result<u64, String> {
     let mut total = 0;
-    for items in input {
-        let first = items[0..items.len() / 2].chars().collect::<HashSet<char>>();
-        let last = items[items.len() / 2..].chars().collect::<HashSet<char>>();
-        let common_item = *first.intersection(&last).next().unwrap();
-        if common_item.is_lowercase() {
-            total += 1 + (common_item as u64 - 'a' as u64);
-        } else {
-            total += 27 + (common_item as u64 - 'A' as u64);
-        }
+    for rucksack in input {
+        total += ItemSet::intersect_all(&[rucksack.first, rucksack.second]).single_priority()?;
     }
-    total
+    Ok(total)
 }
 
 /// Solves AOC 2022 Day 3 Part 2 // Returns the total prioritisation of the common item between each
 /// three elf group.
-fn solve_part2(input: &[String]) -> u64 {
+fn solve_part2(input: &[Rucksack]) -> Result<u64, String> {
+    get_group_badge_total(input, GROUP_SIZE)
+}
+
+/// Gets the total prioritisation of the badge item (the item common to all rucksacks in the group)
+/// across the groups of the given size. Returns an error if the rucksacks cannot be divided evenly
+/// into groups or a group does not have exactly one common item.
+fn get_group_badge_total(rucksacks: &[Rucksack], group_size: usize) -> Result<u64, String> {
+    if group_size == 0 || !rucksacks.len().is_multiple_of(group_size) {
+        return Err(format!(
+            "Day 3 - {} rucksacks cannot be divided evenly into groups of {}",
+            rucksacks.len(),
+            group_size
+        ));
+    }
     let mut total = 0;
-    for i in (0..input.len()).step_by(3) {
-        // Find intersection of
-        let first = input[i].chars().collect::<HashSet<char>>();
-        let second = input[i + 1].chars().collect::<HashSet<char>>();
-        let third = input[i + 2].chars().collect::<HashSet<char>>();
-        // Intersection of first two sets
-        let first_second = first
-            .intersection(&second)
-            .copied()
-            .collect::<HashSet<char>>();
-        // Intersection of third set with first two sets
-        let common_item = *first_second.intersection(&third).next().unwrap();
-        if common_item.is_lowercase() {
-            total += 1 + (common_item as u64 - 'a' as u64);
-        } else {
-            total += 27 + (common_item as u64 - 'A' as u64);
+    for group in rucksacks.chunks(group_size) {
+        let sets = group
+            .iter()
+            .map(|r| r.all_items())
+            .collect::<Vec<ItemSet>>();
+        total += ItemSet::intersect_all(&sets).single_priority()?;
+    }
+    Ok(total)
+}
+
+/// Represents the contents of a single rucksack, split into its two compartments.
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+struct Rucksack {
+    first: ItemSet,
+    second: ItemSet,
+}
+
+impl Rucksack {
+    /// Gets the set of items held in either compartment of the rucksack.
+    fn all_items(&self) -> ItemSet {
+        self.first.union(&self.second)
+    }
+}
+
+/// Represents a set of rucksack item types. Stored as a bitmask where bit n is set if the item
+/// with priority n is present in the set.
+#[derive(Copy, Clone, PartialEq, Eq, Debug, Default)]
+struct ItemSet {
+    mask: u64,
+}
+
+impl ItemSet {
+    /// Creates a new item set containing each of the items in the given string. Returns an error if
+    /// any of the characters is not a valid item type.
+    fn from_items(items: &str) -> Result<Self, String> {
+        let mut mask = 0;
+        for item in items.chars() {
+            match ItemSet::get_priority(item) {
+                Some(priority) => mask |= 1 << priority,
+                None => return Err(format!("Day 3 - invalid item type '{}'", item)),
+            }
+        }
+        Ok(Self { mask })
+    }
+
+    /// Gets the priority of the item type (a-z is 1-26, A-Z is 27-52). Returns None if the
+    /// character is not a valid item type.
+    fn get_priority(item: char) -> Option<u64> {
+        match item {
+            'a'..='z' => Some(1 + (item as u64 - 'a' as u64)),
+            'A'..='Z' => Some(27 + (item as u64 - 'A' as u64)),
+            _ => None,
+        }
+    }
+
+    /// Returns the set of items present in both the current and other set.
+    fn intersection(&self, other: &ItemSet) -> ItemSet {
+        ItemSet {
+            mask: self.mask & other.mask,
+        }
+    }
+
+    /// Returns the set of items present in either the current or other set.
+    fn union(&self, other: &ItemSet) -> ItemSet {
+        ItemSet {
+            mask: self.mask | other.mask,
+        }
+    }
+
+    /// Returns the set of items common to all of the given sets. The intersection of no sets is
+    /// the empty set.
+    fn intersect_all(sets: &[ItemSet]) -> ItemSet {
+        match sets.split_first() {
+            Some((first, rest)) => rest.iter().fold(*first, |acc, set| acc.intersection(set)),
+            None => ItemSet::default(),
+        }
+    }
+
+    /// Gets the priorities of the items in the set, in ascending order.
+    fn priorities(&self) -> Vec<u64> {
+        (1..=52).filter(|p| self.mask & (1 << p) != 0).collect()
+    }
+
+    /// Gets the priority of the only item in the set. Returns an error if the set does not contain
+    /// exactly one item.
+    fn single_priority(&self) -> Result<u64, String> {
+        match self.priorities()[..] {
+            [priority] => Ok(priority),
+            ref priorities => Err(format!(
+                "Day 3 - expected exactly one common item, found {}",
+                priorities.len()
+            )),
         }
     }
-    total
 }
 
 #[cfg(test)]
@@ -101,16 +209,65 @@ mod test {
     /// Tests the Day 3 Part 1 solver method against the actual problem solution.
     #[test]
     fn test_day03_part1_actual() {
-        let input = process_input_file(PROBLEM_INPUT_FILE);
+        let input = process_input_file(PROBLEM_INPUT_FILE).unwrap();
         let solution = solve_part1(&input);
-        assert_eq!(8240, solution);
+        assert_eq!(Ok(8240), solution);
     }
 
     /// Tests the Day 3 Part 2 solver method against the actual problem solution.
     #[test]
     fn test_day03_part2_actual() {
-        let input = process_input_file(PROBLEM_INPUT_FILE);
+        let input = process_input_file(PROBLEM_INPUT_FILE).unwrap();
         let solution = solve_part2(&input);
-        assert_eq!(2587, solution);
+        assert_eq!(Ok(2587), solution);
+    }
+
+    /// Tests the Day 3 Part 1 solver method against the example input.
+    #[test]
+    fn test_day03_part1_t001() {
+        let input = process_input_file("./input/test/day03_t001.txt").unwrap();
+        let solution = solve_part1(&input);
+        assert_eq!(Ok(157), solution);
+    }
+
+    /// Tests the Day 3 Part 2 solver method against the example input.
+    #[test]
+    fn test_day03_part2_t001() {
+        let input = process_input_file("./input/test/day03_t001.txt").unwrap();
+        let solution = solve_part2(&input);
+        assert_eq!(Ok(70), solution);
+    }
+
+    /// Tests that group sizes which do not divide the rucksacks evenly and lines that cannot be split
+    /// into valid compartments are rejected.
+    #[test]
+    fn test_day03_validation_t001() {
+        let input = process_input_file("./input/test/day03_t001.txt").unwrap();
+        assert!(get_group_badge_total(&input, 4).is_err());
+        assert!(get_group_badge_total(&input, 0).is_err());
+        assert!(parse_rucksacks("abcab").is_err());
+        assert!(parse_rucksacks("ab1b").is_err());
+        assert!(parse_rucksacks("aéb").is_err());
+        assert!(parse_rucksacks("abéa").is_err());
+    }
+
+    /// Tests the item set intersection and priority mapping across an arbitrary number of sets.
+    #[test]
+    fn test_day03_item_set_intersection() {
+        let sets = ["vJrwpWtwJgWr", "hcsFMMfFFhFp", "rsFMfFZSrLrFZsSL"]
+            .iter()
+            .map(|s| ItemSet::from_items(s).unwrap())
+            .collect::<Vec<ItemSet>>();
+        assert_eq!(
+            Ok(16),
+            ItemSet::intersect_all(&sets[0..2]).single_priority()
+        );
+        assert_eq!(Ok(18), sets[0].intersection(&sets[2]).single_priority());
+        assert_eq!(
+            vec![6, 19, 32, 39],
+            sets[1].intersection(&sets[2]).priorities()
+        );
+        assert!(ItemSet::intersect_all(&sets).priorities().is_empty());
+        assert!(ItemSet::intersect_all(&[]).priorities().is_empty());
     }
 }
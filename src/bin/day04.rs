@@ -1,7 +1,10 @@
+use std::collections::HashMap;
 use std::fs;
 use std::time::Instant;
 
 use regex::Regex;
+use strum::IntoEnumIterator;
+use strum_macros::EnumIter;
 
 const PROBLEM_NAME: &str = "Camp Cleanup";
 const PROBLEM_INPUT_FILE: &str = "./input/day04.txt";
@@ -28,6 +31,10 @@ pub fn main() {
     println!("AOC 2022 Day {} - \"{}\"", PROBLEM_DAY, PROBLEM_NAME);
     println!("[+] Part 1: {}", p1_solution);
     println!("[+] Part 2: {}", p2_solution);
+    println!("Interval relations:");
+    for (relation, count) in get_relation_report(&input) {
+        println!("[+] {:?}: {}", relation, count);
+    }
     println!("~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~");
     println!("Execution times:");
     println!("[+] Input:  {:.2?}", input_parser_duration);
@@ -41,14 +48,13 @@ pub fn main() {
 }
 
 /// Processes the AOC 2022 Day 4 input file in the format required by the solver functions.
-/// Returned value is vector of four-tuples containing the lower and upper limits of the ranges
-/// specified in the lines of the input file.
-fn process_input_file(filename: &str) -> Vec<(u64, u64, u64, u64)> {
+/// Returned value is vector of section range pairs specified in the lines of the input file.
+fn process_input_file(filename: &str) -> Vec<(SectionRange, SectionRange)> {
     // Read contents of problem input file
     let raw_input = fs::read_to_string(filename).unwrap();
     // Process input file contents into data structure
     let regex_line = Regex::new(r"^(\d+)-(\d+),(\d+)-(\d+)$").unwrap();
-    let mut ranges: Vec<(u64, u64, u64, u64)> = vec![];
+    let mut ranges: Vec<(SectionRange, SectionRange)> = vec![];
     for line in raw_input.lines() {
         let line = line.trim();
         if line.is_empty() {
@@ -59,77 +65,152 @@ fn process_input_file(filename: &str) -> Vec<(u64, u64, u64, u64)> {
         let first_right = captures[2].parse::<u64>().unwrap();
         let second_left = captures[3].parse::<u64>().unwrap();
         let second_right = captures[4].parse::<u64>().unwrap();
-        ranges.push((first_left, first_right, second_left, second_right));
+        ranges.push((
+            SectionRange::new(first_left, first_right),
+            SectionRange::new(second_left, second_right),
+        ));
     }
     ranges
 }
 
 /// Solves AOC 2022 Day 4 Part 1 // Returns the number of range pairs where one range fully contains
 /// the other range.
-fn solve_part1(input: &[(u64, u64, u64, u64)]) -> u64 {
-    let mut count = 0;
-    for range_pair in input {
-        if check_for_whole_overlap(range_pair) {
-            count += 1;
-        }
-    }
-    count
+fn solve_part1(input: &[(SectionRange, SectionRange)]) -> u64 {
+    count_pairs_with_relation(input, IntervalRelation::is_containment)
 }
 
 /// Solves AOC 2022 Day 4 Part 2 // Returns the number of range pairs where the two ranges overlap
 /// in whole or in part
-fn solve_part2(input: &[(u64, u64, u64, u64)]) -> u64 {
-    let mut count = 0;
-    for range_pair in input {
-        if check_for_whole_overlap(range_pair) || check_for_partial_overlap(range_pair) {
-            count += 1;
-        }
-    }
-    count
+fn solve_part2(input: &[(SectionRange, SectionRange)]) -> u64 {
+    count_pairs_with_relation(input, IntervalRelation::is_overlapping)
 }
 
-/// Checks if one of the ranges in the pair fully contains the other.
-fn check_for_whole_overlap(range_pair: &(u64, u64, u64, u64)) -> bool {
-    let (first_left, first_right, second_left, second_right) = range_pair;
-    // Check if first range fully contains the second range
-    if first_left <= second_left && first_right >= second_right {
-        return true;
-    }
-    // Check if second range fully contains the first range
-    if second_left <= first_left && second_right >= first_right {
-        return true;
+/// Counts the number of range pairs whose interval relation matches the given filter.
+fn count_pairs_with_relation(
+    pairs: &[(SectionRange, SectionRange)],
+    filter: fn(&IntervalRelation) -> bool,
+) -> u64 {
+    pairs
+        .iter()
+        .map(|(first, second)| first.classify(second))
+        .filter(filter)
+        .count() as u64
+}
+
+/// Generates a report of the number of range pairs with each of the interval relations. Every
+/// relation is included in the report, in declaration order, even if its count is zero.
+fn get_relation_report(pairs: &[(SectionRange, SectionRange)]) -> Vec<(IntervalRelation, u64)> {
+    let mut counts: HashMap<IntervalRelation, u64> = HashMap::new();
+    for (first, second) in pairs {
+        *counts.entry(first.classify(second)).or_insert(0) += 1;
     }
-    false
+    IntervalRelation::iter()
+        .map(|rel| (rel, *counts.get(&rel).unwrap_or(&0)))
+        .collect::<Vec<(IntervalRelation, u64)>>()
 }
 
-/// Checks if there is a partial overlap between the ranges.
-fn check_for_partial_overlap(range_pair: &(u64, u64, u64, u64)) -> bool {
-    // Calculate minimum length of range of the two ranges
-    let (first_left, first_right, second_left, second_right) = range_pair;
-    let first_len = first_right - first_left + 1;
-    let second_len = second_right - second_left + 1;
-    let min_len = {
-        if first_len < second_len {
-            first_len
-        } else {
-            second_len
-        }
-    };
-    // First range is to left
-    if first_left < second_right && first_right >= second_left {
-        let overlap_len = first_right - second_left + 1;
-        if overlap_len < min_len {
-            return true;
+/// Represents an inclusive range of section IDs assigned to an elf.
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+struct SectionRange {
+    start: u64,
+    end: u64,
+}
+
+impl SectionRange {
+    /// Creates a new section range covering sections from start to end (inclusive). The bounds are
+    /// swapped if given in the wrong order.
+    fn new(start: u64, end: u64) -> Self {
+        Self {
+            start: start.min(end),
+            end: start.max(end),
         }
     }
-    // Second range is to left
-    if second_left < first_right && second_right >= first_left {
-        let overlap_len = second_right - first_left + 1;
-        if overlap_len < min_len {
-            return true;
+
+    /// Classifies the relation of the current range to the other range. Sections are discrete, so
+    /// ranges are treated as covering whole sections - two ranges "meet" when they are adjacent but
+    /// share no sections, and they "overlap" if they share at least one section.
+    fn classify(&self, other: &SectionRange) -> IntervalRelation {
+        // Compare using exclusive upper bounds so adjacent ranges meet rather than overlap
+        let (a_start, a_end) = (self.start, self.end + 1);
+        let (b_start, b_end) = (other.start, other.end + 1);
+        if a_end < b_start {
+            IntervalRelation::Before
+        } else if b_end < a_start {
+            IntervalRelation::After
+        } else if a_end == b_start {
+            IntervalRelation::Meets
+        } else if b_end == a_start {
+            IntervalRelation::MetBy
+        } else if a_start == b_start && a_end == b_end {
+            IntervalRelation::Equals
+        } else if a_start == b_start {
+            if a_end < b_end {
+                IntervalRelation::Starts
+            } else {
+                IntervalRelation::StartedBy
+            }
+        } else if a_end == b_end {
+            if a_start > b_start {
+                IntervalRelation::Finishes
+            } else {
+                IntervalRelation::FinishedBy
+            }
+        } else if a_start > b_start && a_end < b_end {
+            IntervalRelation::During
+        } else if a_start < b_start && a_end > b_end {
+            IntervalRelation::Contains
+        } else if a_start < b_start {
+            IntervalRelation::Overlaps
+        } else {
+            IntervalRelation::OverlappedBy
         }
     }
-    false
+}
+
+/// Represents the thirteen relations of Allen's interval algebra, describing how a first interval
+/// is positioned relative to a second interval.
+#[derive(Copy, Clone, PartialEq, Eq, Hash, Debug, EnumIter)]
+enum IntervalRelation {
+    Before,
+    Meets,
+    Overlaps,
+    Starts,
+    During,
+    Finishes,
+    Equals,
+    FinishedBy,
+    Contains,
+    StartedBy,
+    OverlappedBy,
+    MetBy,
+    After,
+}
+
+impl IntervalRelation {
+    /// Checks if the relation means one of the intervals fully contains the other.
+    fn is_containment(&self) -> bool {
+        matches!(
+            self,
+            IntervalRelation::Starts
+                | IntervalRelation::During
+                | IntervalRelation::Finishes
+                | IntervalRelation::Equals
+                | IntervalRelation::FinishedBy
+                | IntervalRelation::Contains
+                | IntervalRelation::StartedBy
+        )
+    }
+
+    /// Checks if the relation means the intervals share at least one point.
+    fn is_overlapping(&self) -> bool {
+        !matches!(
+            self,
+            IntervalRelation::Before
+                | IntervalRelation::Meets
+                | IntervalRelation::MetBy
+                | IntervalRelation::After
+        )
+    }
 }
 
 #[cfg(test)]
@@ -151,4 +232,39 @@ mod test {
         let solution = solve_part2(&input);
         assert_eq!(835, solution);
     }
+
+    /// Tests the Day 4 interval relation report against the example input.
+    #[test]
+    fn test_day04_relation_report_t001() {
+        let input = process_input_file("./input/test/day04_t001.txt");
+        let report = get_relation_report(&input);
+        let count = |rel: IntervalRelation| report.iter().find(|(r, _)| *r == rel).unwrap().1;
+        assert_eq!(13, report.len());
+        assert_eq!(1, count(IntervalRelation::Before));
+        assert_eq!(1, count(IntervalRelation::Meets));
+        assert_eq!(2, count(IntervalRelation::Overlaps));
+        assert_eq!(1, count(IntervalRelation::Contains));
+        assert_eq!(1, count(IntervalRelation::Finishes));
+        assert_eq!(2, solve_part1(&input));
+        assert_eq!(4, solve_part2(&input));
+    }
+
+    /// Tests the classification of section ranges into each of the interval relations.
+    #[test]
+    fn test_day04_classify_relations() {
+        let r = |start, end| SectionRange::new(start, end);
+        assert_eq!(IntervalRelation::Before, r(1, 2).classify(&r(4, 5)));
+        assert_eq!(IntervalRelation::Meets, r(1, 3).classify(&r(4, 5)));
+        assert_eq!(IntervalRelation::Overlaps, r(1, 4).classify(&r(4, 5)));
+        assert_eq!(IntervalRelation::Starts, r(4, 4).classify(&r(4, 5)));
+        assert_eq!(IntervalRelation::During, r(3, 4).classify(&r(2, 5)));
+        assert_eq!(IntervalRelation::Finishes, r(5, 5).classify(&r(4, 5)));
+        assert_eq!(IntervalRelation::Equals, r(4, 5).classify(&r(4, 5)));
+        assert_eq!(IntervalRelation::FinishedBy, r(4, 5).classify(&r(5, 5)));
+        assert_eq!(IntervalRelation::Contains, r(2, 5).classify(&r(3, 4)));
+        assert_eq!(IntervalRelation::StartedBy, r(4, 5).classify(&r(4, 4)));
+        assert_eq!(IntervalRelation::OverlappedBy, r(4, 5).classify(&r(1, 4)));
+        assert_eq!(IntervalRelation::MetBy, r(4, 5).classify(&r(1, 3)));
+        assert_eq!(IntervalRelation::After, r(4, 5).classify(&r(1, 2)));
+    }
 }
@@ -4,6 +4,8 @@ use std::time::Instant;
 
 use regex::Regex;
 
+use aoc2022::utils::logistics::{Crane, CrateMover9000, CrateMover9001, CrateReplay};
+
 const PROBLEM_NAME: &str = "Supply Stacks";
 const PROBLEM_INPUT_FILE: &str = "./input/day05.txt";
 const PROBLEM_DAY: u64 = 5;
@@ -16,15 +18,15 @@ type ProblemInput = (Vec<VecDeque<char>>, Vec<(usize, usize, usize)>);
 pub fn main() {
     let start = Instant::now();
     // Input processing
-    let input = process_input_file(PROBLEM_INPUT_FILE);
+    let input = process_input_file(PROBLEM_INPUT_FILE).unwrap();
     let input_parser_timestamp = Instant::now();
     let input_parser_duration = input_parser_timestamp.duration_since(start);
     // Solve part 1
-    let p1_solution = solve_part1(&input);
+    let p1_solution = solve_part1(&input).unwrap();
     let p1_timestamp = Instant::now();
     let p1_duration = p1_timestamp.duration_since(input_parser_timestamp);
     // Solve part 2
-    let p2_solution = solve_part2(&input);
+    let p2_solution = solve_part2(&input).unwrap();
     let p2_timestamp = Instant::now();
     let p2_duration = p2_timestamp.duration_since(p1_timestamp);
     // Print results
@@ -45,120 +47,262 @@ pub fn main() {
 }
 
 /// Processes the AOC 2022 Day 5 input file in the format required by the solver functions. Returned
-/// value is tuple containing the vectors of crate stacks and move instructions.
-fn process_input_file(filename: &str) -> ProblemInput {
+/// value is tuple containing the vectors of crate stacks and move instructions, or an error if the
+/// stack drawing or move instructions are malformed.
+fn process_input_file(filename: &str) -> Result<ProblemInput, String> {
     // Read contents of problem input file
     let raw_input = fs::read_to_string(filename).unwrap();
-    // Initialise the output structures
-    let mut stacks: Vec<VecDeque<char>> = vec![];
-    for _ in 0..9 {
-        stacks.push(VecDeque::<char>::new());
+    // Process input file contents into data structure
+    parse_input(&raw_input)
+}
+
+/// Parses the stack drawing and move instructions from the raw input. The number of stacks is
+/// taken from the numeric label row at the bottom of the drawing, and the drawing is separated from
+/// the move instructions by a blank line.
+fn parse_input(raw_input: &str) -> Result<ProblemInput, String> {
+    let lines = raw_input.lines().collect::<Vec<&str>>();
+    // Find the blank line separating the stack drawing from the move instructions
+    let separator = match lines.iter().position(|line| line.trim().is_empty()) {
+        Some(separator) => separator,
+        None => {
+            return Err(String::from(
+                "Day 5 - missing blank line after stack drawing",
+            ))
+        }
+    };
+    if separator == 0 {
+        return Err(String::from("Day 5 - missing stack drawing"));
     }
-    let mut move_instructions: Vec<(usize, usize, usize)> = vec![];
-    // Create the regexes
-    let stack_regex = Regex::new(concat!(
-        r#"^(    |\[[A-Z]\] )(    |\[[A-Z]\] )(    |\[[A-Z]\] )(    |\[[A-Z]\] )"#,
-        r#"(    |\[[A-Z]\] )(    |\[[A-Z]\] )(    |\[[A-Z]\] )(    |\[[A-Z]\] )"#,
-        r#"(   |\[[A-Z]\])$"#,
-    ))
-    .unwrap();
-    let crate_regex = Regex::new(r"\[([A-Z])\]").unwrap();
-    let move_regex = Regex::new(r"^move (\d+) from (\d+) to (\d+)$").unwrap();
-    // Process the lines
-    let mut line_count = 0;
-    for line in raw_input.lines() {
-        line_count += 1;
-        // Process crate stack lines
-        if (1..=8).contains(&line_count) {
-            let captures = stack_regex.captures(line).unwrap();
-            for i in 1..=9 {
-                if crate_regex.is_match(&captures[i]) {
-                    let c = crate_regex.captures(&captures[i]).unwrap()[1]
-                        .chars()
-                        .next()
-                        .unwrap();
-                    stacks[i - 1].push_front(c);
+    let stacks = parse_stack_drawing(&lines[0..separator])?;
+    let move_instructions =
+        parse_move_instructions(&lines[separator + 1..], separator + 2, &stacks)?;
+    Ok((stacks, move_instructions))
+}
+
+/// Parses the crate stacks from the lines of the stack drawing, with the last line being the row
+/// of stack labels. Crate labels can be any non-space character.
+fn parse_stack_drawing(lines: &[&str]) -> Result<Vec<VecDeque<char>>, String> {
+    // Read the stack labels to determine how many stacks are in the drawing
+    let label_row = lines[lines.len() - 1];
+    let mut stack_count = 0;
+    for label in label_row.split_whitespace() {
+        match label.parse::<usize>() {
+            Ok(n) if n == stack_count + 1 => stack_count = n,
+            _ => {
+                return Err(format!(
+                    "Day 5 - line {}: expected stack label {}, found \"{}\"",
+                    lines.len(),
+                    stack_count + 1,
+                    label
+                ))
+            }
+        }
+    }
+    if stack_count == 0 {
+        return Err(format!("Day 5 - line {}: no stack labels", lines.len()));
+    }
+    let mut stacks: Vec<VecDeque<char>> = vec![VecDeque::new(); stack_count];
+    // Process the crate rows, from the top of the drawing down
+    for (row, line) in lines[0..lines.len() - 1].iter().enumerate() {
+        let chars = line.chars().collect::<Vec<char>>();
+        if chars.len() > 4 * stack_count {
+            return Err(format!(
+                "Day 5 - line {}: crate row is wider than the {} labelled stacks",
+                row + 1,
+                stack_count
+            ));
+        }
+        for (i, cell) in chars.chunks(4).enumerate() {
+            match cell {
+                [' ', ' ', ' '] | [' ', ' ', ' ', ' '] => (),
+                ['[', c, ']'] | ['[', c, ']', ' '] if *c != ' ' => stacks[i].push_front(*c),
+                _ => {
+                    return Err(format!(
+                        "Day 5 - line {}: malformed crate in stack {}",
+                        row + 1,
+                        i + 1
+                    ))
                 }
             }
-        // Process move instruction lines
-        } else if line_count >= 11 {
-            let line = line.trim();
-            if line.is_empty() {
-                continue;
+        }
+    }
+    Ok(stacks)
+}
+
+/// Parses the move instructions from the given lines. The line number of the first instruction
+/// line is used for error reporting. Instructions referring to stacks that are not in the drawing
+/// are rejected.
+fn parse_move_instructions(
+    lines: &[&str],
+    first_line_number: usize,
+    stacks: &[VecDeque<char>],
+) -> Result<Vec<(usize, usize, usize)>, String> {
+    let move_regex = Regex::new(r"^move (\d+) from (\d+) to (\d+)$").unwrap();
+    let mut move_instructions: Vec<(usize, usize, usize)> = vec![];
+    for (i, line) in lines.iter().enumerate() {
+        let line = line.trim();
+        if line.is_empty() {
+            continue;
+        }
+        let line_number = first_line_number + i;
+        let captures = match move_regex.captures(line) {
+            Some(captures) => captures,
+            None => {
+                return Err(format!(
+                    "Day 5 - line {}: bad move instruction \"{}\"",
+                    line_number, line
+                ))
+            }
+        };
+        let quantity = captures[1].parse::<usize>().unwrap();
+        let mut stack_indices = [0; 2];
+        for (j, label) in [&captures[2], &captures[3]].iter().enumerate() {
+            match label.parse::<usize>() {
+                Ok(n) if (1..=stacks.len()).contains(&n) => stack_indices[j] = n - 1,
+                _ => {
+                    return Err(format!(
+                        "Day 5 - line {}: stack {} does not exist",
+                        line_number, label
+                    ))
+                }
             }
-            let captures = move_regex.captures(line).unwrap();
-            let quantity = captures[1].parse::<usize>().unwrap();
-            let from = captures[2].parse::<usize>().unwrap() - 1;
-            let to = captures[3].parse::<usize>().unwrap() - 1;
-            move_instructions.push((quantity, from, to))
         }
+        move_instructions.push((quantity, stack_indices[0], stack_indices[1]));
     }
-    (stacks, move_instructions)
+    Ok(move_instructions)
 }
 
 /// Solves AOC 2022 Day 5 Part 1 // Returns the crates at the top of each stack after processing
 /// the movement instructions.
-fn solve_part1(input: &ProblemInput) -> String {
-    let mut stacks = input.0.clone();
-    // Move crates
-    for (quantity, from, to) in input.1.iter() {
-        for _ in 0..*quantity {
-            let c = stacks[*from].pop_back().unwrap();
-            stacks[*to].push_back(c);
-        }
-    }
+fn solve_part1(input: &ProblemInput) -> Result<String, String> {
+    let stacks = run_crane(&CrateMover9000, input)?;
     // Construct the output string
-    construct_output_string(&stacks)
+    Ok(construct_output_string(&stacks))
 }
 
 /// Solves AOC 2022 Day 5 Part 2 // Returns the crates at the top of each stack after processing
 /// the movement instructions, with the crane picking up and moving the crates at once rather than
 /// one-by-one.
-fn solve_part2(input: &ProblemInput) -> String {
-    let mut stacks = input.0.clone();
-    // Move crates
-    for (quantity, from, to) in input.1.iter() {
-        // Pick up the stack of crates
-        let mut move_queue = VecDeque::<char>::new();
-        for _ in 0..*quantity {
-            let c = stacks[*from].pop_back().unwrap();
-            move_queue.push_front(c);
-        }
-        // Move the stack of crates to the new location
-        for _ in 0..*quantity {
-            stacks[*to].push_back(move_queue.pop_front().unwrap());
-        }
-    }
+fn solve_part2(input: &ProblemInput) -> Result<String, String> {
+    let stacks = run_crane(&CrateMover9001, input)?;
     // Construct the output string
-    construct_output_string(&stacks)
+    Ok(construct_output_string(&stacks))
 }
 
-/// Creates the output string by joining together the last crate in each of the stacks.
+/// Applies all of the move instructions to the initial stacks using the given crane. Returns the
+/// final state of the stacks.
+fn run_crane(crane: &dyn Crane, input: &ProblemInput) -> Result<Vec<VecDeque<char>>, String> {
+    let mut replay = CrateReplay::new(crane, &input.0, &input.1);
+    while replay.step()? {}
+    Ok(replay.current().to_vec())
+}
+
+/// Creates the output string by joining together the last crate in each of the stacks. Empty
+/// stacks are represented by a space.
 fn construct_output_string(stacks: &[VecDeque<char>]) -> String {
-    let mut output = String::new();
-    for stack in stacks.iter() {
-        output.push(stack[stack.len() - 1]);
-    }
-    output
+    stacks
+        .iter()
+        .map(|stack| *stack.back().unwrap_or(&' '))
+        .collect::<String>()
 }
 
 #[cfg(test)]
 mod test {
     use super::*;
+    use aoc2022::utils::logistics::{render_stacks, MaxLiftCrane};
 
     /// Tests the Day 5 Part 1 solver method against the actual problem solution.
     #[test]
     fn test_day05_part1_actual() {
-        let input = process_input_file(PROBLEM_INPUT_FILE);
-        let solution = solve_part1(&input);
+        let input = process_input_file(PROBLEM_INPUT_FILE).unwrap();
+        let solution = solve_part1(&input).unwrap();
         assert_eq!("VWLCWGSDQ", solution);
     }
 
     /// Tests the Day 5 Part 2 solver method against the actual problem solution.
     #[test]
     fn test_day05_part2_actual() {
-        let input = process_input_file(PROBLEM_INPUT_FILE);
-        let solution = solve_part2(&input);
+        let input = process_input_file(PROBLEM_INPUT_FILE).unwrap();
+        let solution = solve_part2(&input).unwrap();
         assert_eq!("TCGLQSLPW", solution);
     }
+
+    /// Tests the Day 5 Part 1 solver method against the example input.
+    #[test]
+    fn test_day05_part1_t001() {
+        let input = process_input_file("./input/test/day05_t001.txt").unwrap();
+        let solution = solve_part1(&input).unwrap();
+        assert_eq!("CMZ", solution);
+    }
+
+    /// Tests the Day 5 Part 2 solver method against the example input.
+    #[test]
+    fn test_day05_part2_t001() {
+        let input = process_input_file("./input/test/day05_t001.txt").unwrap();
+        let solution = solve_part2(&input).unwrap();
+        assert_eq!("MCD", solution);
+    }
+
+    /// Tests that moves referring to missing stacks or popping from empty stacks produce errors.
+    #[test]
+    fn test_day05_bad_moves() {
+        let drawing = "[a]    \n[#] [b]\n 1   2 \n\n";
+        assert!(parse_input(&format!("{}move 1 from 3 to 1\n", drawing)).is_err());
+        assert!(parse_input(&format!("{}move 1 from 1 to 0\n", drawing)).is_err());
+        let input = parse_input(&format!("{}move 2 from 2 to 1\n", drawing)).unwrap();
+        assert!(solve_part1(&input).is_err());
+        let input = parse_input(&format!("{}move 1 from 2 to 1\n", drawing)).unwrap();
+        assert_eq!("b ", solve_part1(&input).unwrap());
+    }
+
+    /// Tests the replay of the example input, including rendering and undoing moves.
+    #[test]
+    fn test_day05_replay_t001() {
+        let raw_input = fs::read_to_string("./input/test/day05_t001.txt").unwrap();
+        let input = parse_input(&raw_input).unwrap();
+        let drawing = raw_input.lines().take(4).collect::<Vec<&str>>().join("\n");
+        let mut replay = CrateReplay::new(&CrateMover9000, &input.0, &input.1);
+        assert_eq!(drawing, render_stacks(replay.current()));
+        let first = replay.next().unwrap().unwrap();
+        assert_eq!(
+            "[D]        \n[N] [C]    \n[Z] [M] [P]\n 1   2   3 ",
+            render_stacks(&first)
+        );
+        assert_eq!(3, replay.count());
+        let mut replay = CrateReplay::new(&CrateMover9000, &input.0, &input.1);
+        while replay.step().unwrap() {}
+        assert_eq!(4, replay.moves_applied());
+        assert!(replay.undo());
+        assert_eq!("M Z", construct_output_string(replay.current()));
+        assert!(replay.undo() && replay.undo() && replay.undo());
+        assert!(!replay.undo());
+        assert_eq!(drawing, render_stacks(replay.current()));
+    }
+
+    /// Tests that the max-lift crane matches the other crane models at the limits of its lift.
+    #[test]
+    fn test_day05_max_lift_crane_t001() {
+        let input = process_input_file("./input/test/day05_t001.txt").unwrap();
+        let single = run_crane(&MaxLiftCrane::new(1).unwrap(), &input).unwrap();
+        assert_eq!("CMZ", construct_output_string(&single));
+        let unlimited = run_crane(&MaxLiftCrane::new(3).unwrap(), &input).unwrap();
+        assert_eq!("MCD", construct_output_string(&unlimited));
+        let double = run_crane(&MaxLiftCrane::new(2).unwrap(), &input).unwrap();
+        assert_eq!("MCZ", construct_output_string(&double));
+        assert!(MaxLiftCrane::new(0).is_err());
+    }
+
+    /// Tests that the replay stops after yielding the error for a move that cannot be carried out.
+    #[test]
+    fn test_day05_replay_bad_move_t001() {
+        let input = process_input_file("./input/test/day05_t001.txt").unwrap();
+        let moves = [(1, 1, 0), (5, 0, 2), (1, 0, 1)];
+        let mut replay = CrateReplay::new(&CrateMover9000, &input.0, &moves);
+        assert!(replay.next().unwrap().is_ok());
+        assert!(replay.next().unwrap().unwrap_err().starts_with("move 2:"));
+        assert!(replay.next().is_none());
+        assert_eq!(1, replay.moves_applied());
+        let replay = CrateReplay::new(&CrateMover9000, &input.0, &moves);
+        assert_eq!(2, replay.count());
+    }
 }
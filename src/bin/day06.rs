@@ -1,7 +1,9 @@
-use std::collections::HashSet;
-use std::fs;
+use std::fs::File;
+use std::path::{Path, PathBuf};
 use std::time::Instant;
 
+use aoc2022::utils::comms::find_marker_index;
+
 const PROBLEM_NAME: &str = "Tuning Trouble";
 const PROBLEM_INPUT_FILE: &str = "./input/day06.txt";
 const PROBLEM_DAY: u64 = 6;
@@ -40,19 +42,26 @@ pub fn main() {
 }
 
 /// Processes the AOC 2022 Day 6 input file in the format required by the solver functions.
-/// Returned value is vector of characters given in the input file.
-fn process_input_file(filename: &str) -> Vec<char> {
-    // Read contents of problem input file
-    let raw_input = fs::read_to_string(filename).unwrap();
-    // Process input file contents into data structure
-    return raw_input.trim().chars().collect::<Vec<char>>();
+/// Returned value is the path of the input file, which each solver opens and reads as a stream so
+/// the signal is not loaded into memory.
+fn process_input_file(filename: &str) -> PathBuf {
+    // Check that the problem input file exists
+    let path = PathBuf::from(filename);
+    assert!(path.is_file(), "Day 6 - input file {} not found!", filename);
+    path
+}
+
+/// Finds the index of the first marker with the given length in the signal read from the input
+/// file.
+fn find_file_marker_index(input: &Path, marker_len: usize) -> Option<usize> {
+    find_marker_index(File::open(input).unwrap(), marker_len).unwrap()
 }
 
 /// Solves AOC 2022 Day 6 Part 1 // Returns the number of characters that need to be processed
 /// before the first start-of-packet marker (four consecutive characters that are different) is
 /// observed.
-fn solve_part1(input: &[char]) -> usize {
-    if let Some(index) = find_marker_index(input, 4) {
+fn solve_part1(input: &Path) -> usize {
+    if let Some(index) = find_file_marker_index(input, 4) {
         return index;
     }
     panic!("Day 6 Part 1 - did not find the start-of-packet marker!");
@@ -61,37 +70,17 @@ fn solve_part1(input: &[char]) -> usize {
 /// Solves AOC 2022 Day 6 Part 2 // Returns the number of characters that need to be processed
 /// before the first start-of-message marker (14 consecutive characters that are different) is
 /// observed.
-fn solve_part2(input: &[char]) -> usize {
-    if let Some(index) = find_marker_index(input, 14) {
+fn solve_part2(input: &Path) -> usize {
+    if let Some(index) = find_file_marker_index(input, 14) {
         return index;
     }
     panic!("Day 6 Part 2 - did not find the start-of-message marker!");
 }
 
-/// Finds the index of the marker (sequence of characters that are different) in the given vector
-/// of characters with the given length. Index is the number of characters from the start of the
-/// given chars to the end of the marker (inclusive).
-fn find_marker_index(chars: &[char], marker_len: usize) -> Option<usize> {
-    for cursor in 0..(chars.len() - marker_len + 1) {
-        let mut window_set: HashSet<char> = HashSet::new();
-        for i in 0..marker_len {
-            // Break early if duplicate character is observed
-            if window_set.contains(&chars[cursor + i]) {
-                break;
-            }
-            window_set.insert(chars[cursor + i]);
-        }
-        // Check if the marker has been found
-        if window_set.len() == marker_len {
-            return Some(cursor + marker_len);
-        }
-    }
-    None
-}
-
 #[cfg(test)]
 mod test {
     use super::*;
+    use aoc2022::utils::comms::{find_all_marker_indices, MarkerDetector};
 
     /// Tests the Day 6 Part 1 solver method against the actual problem solution.
     #[test]
@@ -108,4 +97,41 @@ mod test {
         let solution = solve_part2(&input);
         assert_eq!(3965, solution);
     }
+
+    /// Tests the marker detector against the example signals.
+    #[test]
+    fn test_day06_examples() {
+        let examples = [
+            ("mjqjpqmgbljsphdztnvjfqwrcgsmlb", 7, 19),
+            ("bvwbjplbgvbhsrlpgdmjqwftvncz", 5, 23),
+            ("nznrnfrfntjfmvfwmzdfjlvtqnbhcprsg", 10, 29),
+            ("zcfzfwzzqfrljwzlrfnpqdbhtmscgvjw", 11, 26),
+        ];
+        for (signal, packet, message) in examples {
+            assert_eq!(
+                Some(packet),
+                find_marker_index(signal.as_bytes(), 4).unwrap()
+            );
+            assert_eq!(
+                Some(message),
+                find_marker_index(signal.as_bytes(), 14).unwrap()
+            );
+        }
+    }
+
+    /// Tests that every marker is reported, that short signals do not find a marker and that a
+    /// marker length of 0 is rejected.
+    #[test]
+    fn test_day06_all_markers() {
+        assert_eq!(
+            vec![3, 4, 5, 8],
+            find_all_marker_indices(&b"abcaxxyz"[..], 3).unwrap()
+        );
+        assert_eq!(None, find_marker_index(&b"abc\n"[..], 4).unwrap());
+        assert_eq!(None, find_marker_index(&b""[..], 14).unwrap());
+        assert!(find_all_marker_indices(&b"aaaa"[..], 2).unwrap().is_empty());
+        assert!(find_marker_index(&b"abc"[..], 0).is_err());
+        assert!(find_all_marker_indices(&b"abc"[..], 0).is_err());
+        assert!(MarkerDetector::new(0).is_err());
+    }
 }
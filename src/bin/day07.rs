@@ -1,18 +1,17 @@
-use std::collections::{HashMap, VecDeque};
+use std::fmt;
 use std::fs;
 use std::time::Instant;
 
-use regex::Regex;
+use aoc2022::utils::filesystem::{
+    build_tree, parse_session, validate_session, FsTree, SessionReport,
+};
 
 const PROBLEM_NAME: &str = "No Space Left On Device";
 const PROBLEM_INPUT_FILE: &str = "./input/day07.txt";
 const PROBLEM_DAY: u64 = 7;
 
-/// Represents a file or directory in a file system.
-enum FsItem {
-    File { size: usize },
-    Directory { parent_dir: String, name: String },
-}
+/// Type definition to simplify function signatures.
+type ProblemInput = (FsTree, SessionReport);
 
 /// Processes the AOC 2022 Day 7 input file and solves both parts of the problem. Solutions are
 /// printed to stdout.
@@ -35,6 +34,9 @@ pub fn main() {
     println!("AOC 2022 Day {} - \"{}\"", PROBLEM_DAY, PROBLEM_NAME);
     println!("[+] Part 1: {}", p1_solution);
     println!("[+] Part 2: {}", p2_solution);
+    for anomaly in input.1.anomalies() {
+        println!("[!] {}", anomaly);
+    }
     println!("~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~");
     println!("Execution times:");
     println!("[+] Input:  {:.2?}", input_parser_duration);
@@ -48,149 +50,94 @@ pub fn main() {
 }
 
 /// Processes the AOC 2022 Day 7 input file in the format required by the solver functions.
-/// Returned value is hashmap containing each directory (full path name) mapped to the vector of
-/// fsitems contained in the directory.
-fn process_input_file(filename: &str) -> HashMap<String, Vec<FsItem>> {
+/// Returned value is tuple containing the file system tree built from the terminal session given in
+/// the input file and the validation report for the session.
+fn process_input_file(filename: &str) -> ProblemInput {
     // Read contents of problem input file
-    let binding = fs::read_to_string(filename).unwrap();
-    let raw_input = binding.trim();
+    let raw_input = fs::read_to_string(filename).unwrap();
     // Process input file contents into data structure
-    let mut output: HashMap<String, Vec<FsItem>> = HashMap::new();
-    let mut current_dir: VecDeque<String> = VecDeque::new();
-    let cd_regex = Regex::new(r"^[$] cd (.*)$").unwrap();
-    let file_regex = Regex::new(r"^(\d+) (.*)$").unwrap();
-    let dir_regex = Regex::new(r"^dir (.*)$").unwrap();
-    let lines = raw_input
-        .trim()
-        .lines()
-        .map(|line| line.trim().to_string())
-        .collect::<Vec<String>>();
-    let mut cursor = 0;
-    loop {
-        if cursor >= lines.len() {
-            break;
-        }
-        if cd_regex.is_match(&lines[cursor]) {
-            let caps = cd_regex.captures(&lines[cursor]).unwrap();
-            let dir = caps[1].to_string();
-            if dir == ".." {
-                current_dir.pop_back();
-                cursor += 1;
-            } else if dir == "/" {
-                current_dir.push_back(dir);
-                let cwd = current_dir
-                    .iter()
-                    .cloned()
-                    .collect::<Vec<String>>()
-                    .join("");
-                output.insert(cwd, vec![]);
-                cursor += 2;
-            } else {
-                current_dir.push_back(format!("{}/", dir));
-                let cwd = current_dir
-                    .iter()
-                    .cloned()
-                    .collect::<Vec<String>>()
-                    .join("");
-                output.insert(cwd, vec![]);
-                cursor += 2;
-            }
-        } else {
-            let cwd = current_dir
-                .iter()
-                .cloned()
-                .collect::<Vec<String>>()
-                .join("");
-            loop {
-                if cursor >= lines.len() || cd_regex.is_match(&lines[cursor]) {
-                    break;
-                } else if file_regex.is_match(&lines[cursor]) {
-                    let caps = file_regex.captures(&lines[cursor]).unwrap();
-                    let size = caps[1].parse::<usize>().unwrap();
-                    let file_item = FsItem::File { size };
-                    output.get_mut(&cwd).unwrap().push(file_item);
-                } else if dir_regex.is_match(&lines[cursor]) {
-                    let caps = dir_regex.captures(&lines[cursor]).unwrap();
-                    let name = caps[1].to_string();
-                    let dir_item = FsItem::Directory {
-                        parent_dir: cwd.to_string(),
-                        name,
-                    };
-                    output.get_mut(&cwd).unwrap().push(dir_item);
-                } else {
-                    panic!("Day 7 - bad file system item!");
-                }
-                cursor += 1;
-            }
-        }
-    }
-    output
+    let session = parse_session(&raw_input).unwrap();
+    (build_tree(&session).unwrap(), validate_session(&session))
 }
 
 /// Solves AOC 2022 Day 7 Part 1 // Calculates the total size of all directories that have a size of
 /// at most 100,000.
-fn solve_part1(dirs: &HashMap<String, Vec<FsItem>>) -> usize {
-    let mut dir_sizes: HashMap<String, usize> = HashMap::new();
-    find_dir_sizes(dirs, &mut dir_sizes, &String::from("/"));
-    return dir_sizes
-        .values()
-        .copied()
-        .filter(|size| *size <= 100000)
+fn solve_part1(input: &ProblemInput) -> SizeAnswer {
+    let (tree, report) = input;
+    let value = tree
+        .find(|node, size| node.is_dir() && size <= 100000)
+        .iter()
+        .map(|(_, size)| size)
         .sum();
+    SizeAnswer {
+        value,
+        determined: report.sizes_determined(),
+    }
 }
 
 /// Solves AOC 2022 Day 7 Part 2 // Finds the size of the smallest directory that would free up
 /// enough space if deleted.
-fn solve_part2(dirs: &HashMap<String, Vec<FsItem>>) -> usize {
-    let max_fs_size: usize = 70000000;
-    let req_free_space: usize = 30000000;
-    let mut dir_sizes: HashMap<String, usize> = HashMap::new();
-    find_dir_sizes(dirs, &mut dir_sizes, &String::from("/"));
-    let free_space = max_fs_size - dir_sizes.get("/").unwrap();
-    // Calculate extra amount of free space
-    let delta = req_free_space - free_space;
-    let mut candidate_dirs = dir_sizes
-        .values()
-        .copied()
-        .filter(|size| *size >= delta)
-        .collect::<Vec<usize>>();
-    candidate_dirs.sort();
-    candidate_dirs[0]
+fn solve_part2(input: &ProblemInput) -> SizeAnswer {
+    let (tree, report) = input;
+    let max_fs_size: u64 = 70000000;
+    let req_free_space: u64 = 30000000;
+    let used_space = tree.du(tree.root());
+    // Calculate extra amount of free space. Using more than the total disk space means the merged
+    // listings cannot all be right, so the sizes are not determined.
+    let (delta, determined) = match max_fs_size.checked_sub(used_space) {
+        Some(free_space) => (
+            req_free_space.saturating_sub(free_space),
+            report.sizes_determined(),
+        ),
+        None => (used_space - max_fs_size + req_free_space, false),
+    };
+    let value = tree
+        .find(|node, size| node.is_dir() && size >= delta)
+        .iter()
+        .map(|(_, size)| *size)
+        .min()
+        .unwrap();
+    SizeAnswer { value, determined }
 }
 
-/// Finds the sizes of all directories below the given dir. Size of directories are updated into the
-/// dir_sizes hashmap.
-fn find_dir_sizes(
-    dirs: &HashMap<String, Vec<FsItem>>,
-    dir_sizes: &mut HashMap<String, usize>,
-    dir: &String,
-) {
-    let mut total_size = 0;
-    for fs_item in dirs.get(dir).unwrap().iter() {
-        match fs_item {
-            FsItem::Directory { parent_dir, name } => {
-                let cwd = format!("{}{}/", parent_dir, name);
-                find_dir_sizes(dirs, dir_sizes, &cwd);
-                total_size += dir_sizes.get(&cwd).unwrap();
-            }
-            FsItem::File { size } => {
-                total_size += size;
-            }
+/// Represents a solution calculated from the directory sizes, noting whether the sizes were fully
+/// determined by the terminal session or are only lower bounds.
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+struct SizeAnswer {
+    value: u64,
+    determined: bool,
+}
+
+impl fmt::Display for SizeAnswer {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if self.determined {
+            write!(f, "{}", self.value)
+        } else {
+            write!(f, "{} (directory sizes are lower bounds)", self.value)
         }
     }
-    dir_sizes.insert(dir.to_string(), total_size);
 }
 
 #[cfg(test)]
 mod test {
     use super::*;
+    use aoc2022::utils::filesystem::{
+        generate_transcript, parse_tree_listing, tree_from_dir, SessionAnomaly, TranscriptOptions,
+        TraversalOrder,
+    };
 
     /// Tests the Day 7 Part 1 solver method against the actual problem solution.
     #[test]
     fn test_day07_part1_actual() {
         let input = process_input_file(PROBLEM_INPUT_FILE);
         let solution = solve_part1(&input);
-        assert_eq!(1432936, solution);
+        assert_eq!(
+            SizeAnswer {
+                value: 1432936,
+                determined: true
+            },
+            solution
+        );
     }
 
     /// Tests the Day 7 Part 2 solver method against the actual problem solution.
@@ -198,6 +145,247 @@ mod test {
     fn test_day07_part2_actual() {
         let input = process_input_file(PROBLEM_INPUT_FILE);
         let solution = solve_part2(&input);
-        assert_eq!(272298, solution);
+        assert_eq!(
+            SizeAnswer {
+                value: 272298,
+                determined: true
+            },
+            solution
+        );
+    }
+
+    /// Tests the Day 7 Part 1 solver method against the example input.
+    #[test]
+    fn test_day07_part1_t001() {
+        let input = process_input_file("./input/test/day07_t001.txt");
+        let solution = solve_part1(&input);
+        assert_eq!(
+            SizeAnswer {
+                value: 95437,
+                determined: true
+            },
+            solution
+        );
+    }
+
+    /// Tests the Day 7 Part 2 solver method against the example input.
+    #[test]
+    fn test_day07_part2_t001() {
+        let input = process_input_file("./input/test/day07_t001.txt");
+        let solution = solve_part2(&input);
+        assert_eq!(
+            SizeAnswer {
+                value: 24933642,
+                determined: true
+            },
+            solution
+        );
+    }
+
+    /// Tests that the tree built from the example input renders in the problem statement format.
+    #[test]
+    fn test_day07_render_tree_t001() {
+        let (tree, report) = process_input_file("./input/test/day07_t001.txt");
+        assert!(report.is_valid());
+        let expected = [
+            "- / (dir)",
+            "  - a (dir)",
+            "    - e (dir)",
+            "      - i (file, size=584)",
+            "    - f (file, size=29116)",
+            "    - g (file, size=2557)",
+            "    - h.lst (file, size=62596)",
+            "  - b.txt (file, size=14848514)",
+            "  - c.dat (file, size=8504156)",
+            "  - d (dir)",
+            "    - d.ext (file, size=5626152)",
+            "    - d.log (file, size=8033020)",
+            "    - j (file, size=4060174)",
+            "    - k (file, size=7214296)",
+        ];
+        assert_eq!(expected.join("\n"), tree.render_tree());
+        let e = tree.find(|node, _| node.name() == "e")[0];
+        assert_eq!((e.0, 584), e);
+        assert_eq!("/a/e/", tree.path(e.0));
+    }
+
+    /// Tests sessions that return to the root mid-session, list a directory twice and change into
+    /// directories that were never listed.
+    #[test]
+    fn test_day07_irregular_session() {
+        let raw_input = [
+            "$ cd x", "$ cd y", "$ ls", "10 f", "$ cd /", "$ ls", "dir x", "5 g", "$ cd x", "$ ls",
+            "dir y", "$ ls", "dir y", "3 h", "$ cd ..", "$ cd ..", "$ ls", "5 g",
+        ]
+        .join("\n");
+        let tree = build_tree(&parse_session(&raw_input).unwrap()).unwrap();
+        assert_eq!(6, tree.len());
+        assert_eq!(18, tree.du(tree.root()));
+        let x = tree.child(tree.root(), "x").unwrap();
+        assert_eq!(13, tree.du(x));
+        assert!(parse_session("10 f").is_err());
+        assert!(parse_session("$ cd").is_err());
+        assert!(parse_session("$ ls -l").is_err());
+        assert!(build_tree(&parse_session("$ ls\n1 a\n$ cd a").unwrap()).is_err());
+    }
+
+    /// Tests that the validator reports each anomaly in an irregular session with its line number,
+    /// and that never-listed directories make the sizes lower bounds.
+    #[test]
+    fn test_day07_validate_session() {
+        let raw_input = [
+            "$ cd /", "$ ls", "dir a", "1 f", "$ cd ..", "$ cd b", "$ ls", "2 g", "$ cd /", "$ ls",
+            "dir a", "2 f", "$ cd a", "$ ls", "dir c",
+        ]
+        .join("\n");
+        let session = parse_session(&raw_input).unwrap();
+        let report = validate_session(&session);
+        let lines = report
+            .anomalies()
+            .iter()
+            .map(|a| a.line())
+            .collect::<Vec<usize>>();
+        assert_eq!(vec![5, 6, 10], lines);
+        assert_eq!(
+            SessionAnomaly::InconsistentListing {
+                line: 10,
+                path: String::from("/"),
+                first_line: 2
+            },
+            report.anomalies()[2]
+        );
+        assert_eq!(vec![String::from("/a/c/")], report.unlisted_dirs());
+        assert!(!report.sizes_determined());
+        let input = (build_tree(&session).unwrap(), report);
+        assert!(!solve_part1(&input).determined);
+    }
+
+    /// Tests that a directory listed twice with different contents makes the sizes undetermined,
+    /// even when every directory was listed, including when the merged listings overfill the disk.
+    #[test]
+    fn test_day07_inconsistent_listing_sizes() {
+        let raw_input = [
+            "$ cd /", "$ ls", "dir a", "100 f", "$ cd a", "$ ls", "50 g", "$ cd ..", "$ ls",
+            "dir a", "100 f", "$ cd a", "$ ls", "60 h",
+        ]
+        .join("\n");
+        let session = parse_session(&raw_input).unwrap();
+        let report = validate_session(&session);
+        assert!(report.unlisted_dirs().is_empty());
+        assert_eq!(
+            vec![SessionAnomaly::InconsistentListing {
+                line: 13,
+                path: String::from("/a/"),
+                first_line: 6
+            }],
+            report.anomalies()
+        );
+        assert!(!report.sizes_determined());
+        let input = (build_tree(&session).unwrap(), report);
+        assert!(!solve_part1(&input).determined);
+        // Listing the same directory twice with the same contents is consistent
+        let raw_input = ["$ cd /", "$ ls", "100 f", "$ ls", "100 f"].join("\n");
+        let report = validate_session(&parse_session(&raw_input).unwrap());
+        assert!(report.is_valid() && report.sizes_determined());
+        // Merged listings using more than the total disk space
+        let raw_input = ["$ cd /", "$ ls", "40000000 f", "$ ls", "40000000 g"].join("\n");
+        let session = parse_session(&raw_input).unwrap();
+        let input = (build_tree(&session).unwrap(), validate_session(&session));
+        assert_eq!(
+            SizeAnswer {
+                value: 80000000,
+                determined: false
+            },
+            solve_part2(&input)
+        );
+    }
+
+    /// Tests that the transcript generated from the example tree matches the example input, and
+    /// that generating, parsing and regenerating transcripts round-trips for all options.
+    #[test]
+    fn test_day07_generate_transcript_t001() {
+        let raw_input = fs::read_to_string("./input/test/day07_t001.txt").unwrap();
+        let (tree, _) = process_input_file("./input/test/day07_t001.txt");
+        let transcript = generate_transcript(&tree, &TranscriptOptions::default());
+        assert_eq!(raw_input.trim(), transcript);
+        assert_round_trip(&tree);
+    }
+
+    /// Tests the transcript round-trip against synthetic trees generated from a fixed seed, along
+    /// with the round-trip of the nested list tree description.
+    #[test]
+    fn test_day07_generate_transcript_synthetic() {
+        let mut seed: u64 = 2022;
+        for _ in 0..20 {
+            let mut tree = FsTree::new();
+            let mut dirs = vec![tree.root()];
+            for i in 0..40 {
+                // Simple linear congruential generator to pick parents, entry types and sizes
+                seed = seed
+                    .wrapping_mul(6364136223846793005)
+                    .wrapping_add(1442695040888963407);
+                let parent = dirs[(seed >> 33) as usize % dirs.len()];
+                if (seed >> 20).is_multiple_of(3) {
+                    dirs.push(tree.add_dir(parent, &format!("d{}", i)).unwrap());
+                } else {
+                    let size = (seed >> 40) % 100000;
+                    tree.add_file(parent, &format!("f{}.txt", i), size).unwrap();
+                }
+            }
+            assert_round_trip(&tree);
+            let listing = parse_tree_listing(&tree.render_tree()).unwrap();
+            assert_eq!(tree.render_tree(), listing.render_tree());
+        }
+        let listing = "- / (dir)\n  - f (file, size=99999999999999999999)";
+        assert!(parse_tree_listing(listing).is_err());
+    }
+
+    /// Tests that a tree built by walking a directory on disk has the expected contents.
+    #[test]
+    fn test_day07_tree_from_dir() {
+        let root = std::env::temp_dir().join(format!("aoc2022_day07_{}", std::process::id()));
+        fs::create_dir_all(root.join("a").join("e")).unwrap();
+        fs::write(root.join("b.txt"), "0123456789").unwrap();
+        fs::write(root.join("a").join("e").join("i"), "abc").unwrap();
+        fs::create_dir_all(root.join("my docs")).unwrap();
+        fs::write(root.join("my docs").join("notes v1.txt"), "").unwrap();
+        let tree = tree_from_dir(&root);
+        // Names with leading or trailing whitespace cannot be read back from a transcript
+        fs::write(root.join("a").join(" padded"), "").unwrap();
+        let padded = tree_from_dir(&root);
+        fs::remove_dir_all(&root).unwrap();
+        let tree = tree.unwrap();
+        let expected = [
+            "- / (dir)",
+            "  - a (dir)",
+            "    - e (dir)",
+            "      - i (file, size=3)",
+            "  - b.txt (file, size=10)",
+            "  - my docs (dir)",
+            "    - notes v1.txt (file, size=0)",
+        ];
+        assert_eq!(expected.join("\n"), tree.render_tree());
+        assert_round_trip(&tree);
+        assert!(padded.is_err());
+    }
+
+    /// Checks that transcripts generated from the tree with every combination of options are valid
+    /// sessions that rebuild the same tree and regenerate the same transcript.
+    fn assert_round_trip(tree: &FsTree) {
+        for order in [TraversalOrder::DepthFirst, TraversalOrder::BreadthFirst] {
+            for root_jumps in [false, true] {
+                for repeat_listings in [false, true] {
+                    let options = TranscriptOptions::new(order, root_jumps, repeat_listings);
+                    let transcript = generate_transcript(tree, &options);
+                    let session = parse_session(&transcript).unwrap();
+                    let report = validate_session(&session);
+                    assert!(report.is_valid() && report.sizes_determined());
+                    let rebuilt = build_tree(&session).unwrap();
+                    assert_eq!(tree.render_tree(), rebuilt.render_tree());
+                    assert_eq!(tree.du(tree.root()), rebuilt.du(rebuilt.root()));
+                    assert_eq!(transcript, generate_transcript(&rebuilt, &options));
+                }
+            }
+        }
     }
 }
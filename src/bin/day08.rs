@@ -1,6 +1,8 @@
 use std::fs;
 use std::time::Instant;
 
+use aoc2022::utils::cartography::Point2D;
+
 const PROBLEM_NAME: &str = "Treetop Tree House";
 const PROBLEM_INPUT_FILE: &str = "./input/day08.txt";
 const PROBLEM_DAY: u64 = 8;
@@ -44,151 +46,138 @@ fn process_input_file(filename: &str) -> Vec<Vec<u64>> {
     // Read contents of problem input file
     let raw_input = fs::read_to_string(filename).unwrap();
     // Process input file contents into data structure
-    return raw_input
+    parse_tree_heights(&raw_input).unwrap()
+}
+
+/// Parses the grid of tree heights from the raw input. Heights are given either as a single digit
+/// per tree, or as whitespace-separated numbers if any line contains whitespace (allowing heights
+/// above 9). The grid does not need to be square, but all rows must be the same width.
+fn parse_tree_heights(raw_input: &str) -> Result<Vec<Vec<u64>>, String> {
+    let lines = raw_input
         .trim()
         .lines()
-        .map(|line| {
+        .map(|line| line.trim())
+        .collect::<Vec<&str>>();
+    let separated = lines.iter().any(|line| line.contains(char::is_whitespace));
+    let mut tree_heights: Vec<Vec<u64>> = vec![];
+    for (i, line) in lines.iter().enumerate() {
+        let row = if separated {
+            line.split_whitespace()
+                .map(|s| s.parse::<u64>().ok())
+                .collect::<Option<Vec<u64>>>()
+        } else {
             line.chars()
-                .map(|c| c.to_digit(10).unwrap() as u64)
-                .collect::<Vec<u64>>()
-        })
-        .collect::<Vec<Vec<u64>>>();
+                .map(|c| c.to_digit(10).map(|d| d as u64))
+                .collect::<Option<Vec<u64>>>()
+        };
+        let row = match row {
+            Some(row) => row,
+            None => return Err(format!("Day 8 - line {} has a bad tree height", i + 1)),
+        };
+        if !tree_heights.is_empty() && tree_heights[0].len() != row.len() {
+            return Err(format!("Day 8 - line {} has a different width", i + 1));
+        }
+        tree_heights.push(row);
+    }
+    if tree_heights.is_empty() || tree_heights[0].is_empty() {
+        return Err(String::from("Day 8 - empty tree grid"));
+    }
+    Ok(tree_heights)
 }
 
 /// Solves AOC 2022 Day 8 Part 1 // Calculates the number of trees that are visible from outside the
 /// grid.
 fn solve_part1(tree_heights: &[Vec<u64>]) -> usize {
-    let max_y = tree_heights.len() - 1;
-    let max_x = tree_heights[0].len() - 1;
-    let mut total_visible = 0;
-    for y in 0..=max_y {
-        for x in 0..=max_x {
-            // Check if tree is on the edge of the grid
-            if y == 0 || y == max_y {
-                total_visible += 1;
-                continue;
-            }
-            if x == 0 || x == max_x {
-                total_visible += 1;
-                continue;
-            }
-            // Check if current tree is visible from one side
-            if check_left_side_visibility(x, y, tree_heights)
-                || check_top_side_visibility(x, y, tree_heights)
-                || check_right_side_visibility(x, y, tree_heights)
-                || check_bottom_side_visibility(x, y, tree_heights)
-            {
-                total_visible += 1;
-            }
-        }
-    }
-    total_visible
+    compute_tree_maps(tree_heights)
+        .0
+        .iter()
+        .flatten()
+        .filter(|visible| **visible)
+        .count()
 }
 
 /// Solves AOC 2022 Day 8 Part 2 // Calculates the highest "scenic score" possible from any tree.
 fn solve_part2(tree_heights: &[Vec<u64>]) -> usize {
-    let max_y = tree_heights.len() - 1;
-    let max_x = tree_heights[0].len() - 1;
-    let mut max_scenic_score = 0;
-    for y in 0..=max_y {
-        for x in 0..=max_x {
-            // Calculate scenic score from product of LEFT, TOP, RIGHT and BOTTOM viewing distances
-            let mut scenic_score = 1;
-            scenic_score *= get_left_side_viewing_distance(x, y, tree_heights);
-            scenic_score *= get_top_side_viewing_distance(x, y, tree_heights);
-            scenic_score *= get_right_side_viewing_distance(x, y, tree_heights);
-            scenic_score *= get_bottom_side_viewing_distance(x, y, tree_heights);
-            // Calculate scenic score and check if it is the new maximum value
-            if scenic_score > max_scenic_score {
-                max_scenic_score = scenic_score;
-            }
-        }
-    }
-    max_scenic_score
-}
-
-/// Checks if the current tree is visible from the LEFT side of the grid.
-fn check_left_side_visibility(x: usize, y: usize, tree_heights: &[Vec<u64>]) -> bool {
-    for new_x in 0..x {
-        if tree_heights[y][new_x] >= tree_heights[y][x] {
-            return false;
-        }
-    }
-    true
+    find_best_tree(tree_heights).1
 }
 
-/// Checks if the current tree is visible from the TOP side of the grid.
-fn check_top_side_visibility(x: usize, y: usize, tree_heights: &[Vec<u64>]) -> bool {
-    for new_y in 0..y {
-        if tree_heights[new_y][x] >= tree_heights[y][x] {
-            return false;
+/// Finds the tree with the highest scenic score. Returned value is the location of the tree (x is
+/// the column, y is the row) and its scenic score. Ties are broken by the first tree in reading
+/// order.
+fn find_best_tree(tree_heights: &[Vec<u64>]) -> (Point2D, usize) {
+    let (_, scenic_scores) = compute_tree_maps(tree_heights);
+    let mut best = (Point2D::new(0, 0), 0);
+    for (y, row) in scenic_scores.iter().enumerate() {
+        for (x, score) in row.iter().enumerate() {
+            if *score > best.1 {
+                best = (Point2D::new(x as i64, y as i64), *score);
+            }
         }
     }
-    true
+    best
 }
 
-/// Checks if the current tree is visible from the RIGHT side of the grid.
-fn check_right_side_visibility(x: usize, y: usize, tree_heights: &[Vec<u64>]) -> bool {
-    let max_x = tree_heights[0].len() - 1;
-    for new_x in (x + 1)..=max_x {
-        if tree_heights[y][new_x] >= tree_heights[y][x] {
-            return false;
-        }
+/// Computes the visibility map (whether each tree can be seen from outside the grid) and the
+/// scenic score map in O(n) time, where n is the number of trees in the grid.
+fn compute_tree_maps(tree_heights: &[Vec<u64>]) -> (Vec<Vec<bool>>, Vec<Vec<usize>>) {
+    let height = tree_heights.len();
+    let width = tree_heights[0].len();
+    let mut visible = vec![vec![false; width]; height];
+    let mut scenic_scores = vec![vec![1; width]; height];
+    // Sweep along each row and column in both directions
+    let mut lines: Vec<Vec<(usize, usize)>> = vec![];
+    for y in 0..height {
+        let row = (0..width).map(|x| (x, y)).collect::<Vec<(usize, usize)>>();
+        lines.push(row.iter().rev().copied().collect());
+        lines.push(row);
     }
-    true
-}
-
-/// Checks if the current tree is visible from the BOTTOM side of the grid.
-fn check_bottom_side_visibility(x: usize, y: usize, tree_heights: &[Vec<u64>]) -> bool {
-    let max_y = tree_heights.len() - 1;
-    for new_y in (y + 1)..=max_y {
-        if tree_heights[new_y][x] >= tree_heights[y][x] {
-            return false;
-        }
+    for x in 0..width {
+        let col = (0..height).map(|y| (x, y)).collect::<Vec<(usize, usize)>>();
+        lines.push(col.iter().rev().copied().collect());
+        lines.push(col);
     }
-    true
-}
-
-/// Determines the LEFT side viewing distance from the current tree (x,y position).
-fn get_left_side_viewing_distance(x: usize, y: usize, tree_heights: &[Vec<u64>]) -> usize {
-    for i in 0..x {
-        if tree_heights[y][x - i - 1] >= tree_heights[y][x] {
-            return i + 1;
-        }
+    for line in lines {
+        sweep_line(tree_heights, &line, &mut visible, &mut scenic_scores);
     }
-    x
+    (visible, scenic_scores)
 }
 
-/// Determines the TOP side viewing distance from the current tree (x,y position).
-fn get_top_side_viewing_distance(x: usize, y: usize, tree_heights: &[Vec<u64>]) -> usize {
-    for i in 0..y {
-        if tree_heights[y - i - 1][x] >= tree_heights[y][x] {
-            return i + 1;
+/// Sweeps along the line of tree locations (x, y) looking back towards the start of the line. Trees
+/// taller than every tree before them are marked visible, and each tree's scenic score is
+/// multiplied by its viewing distance back along the line. A monotonic stack holds the trees that
+/// could still block the view of later trees, so each tree is pushed and popped at most once.
+fn sweep_line(
+    tree_heights: &[Vec<u64>],
+    line: &[(usize, usize)],
+    visible: &mut [Vec<bool>],
+    scenic_scores: &mut [Vec<usize>],
+) {
+    // Stack of (position along line, height), with heights strictly decreasing from the bottom
+    let mut stack: Vec<(usize, u64)> = vec![];
+    for (i, (x, y)) in line.iter().enumerate() {
+        let h = tree_heights[*y][*x];
+        // Shorter trees can no longer block the view of any later tree
+        while let Some((_, top)) = stack.last() {
+            if *top >= h {
+                break;
+            }
+            stack.pop();
         }
-    }
-    y
-}
-
-/// Determines the RIGHT side viewing distance from the current tree (x,y position).
-fn get_right_side_viewing_distance(x: usize, y: usize, tree_heights: &[Vec<u64>]) -> usize {
-    let max_x = tree_heights[0].len() - 1;
-    for i in 0..(max_x - x) {
-        if tree_heights[y][x + i + 1] >= tree_heights[y][x] {
-            return i + 1;
+        match stack.last() {
+            Some((blocker, _)) => scenic_scores[*y][*x] *= i - blocker,
+            None => {
+                visible[*y][*x] = true;
+                scenic_scores[*y][*x] *= i;
+            }
         }
-    }
-    max_x - x
-}
-
-/// Determines the BOTTOM side viewing distance from the current tree (x,y position).
-fn get_bottom_side_viewing_distance(x: usize, y: usize, tree_heights: &[Vec<u64>]) -> usize {
-    let max_y = tree_heights.len() - 1;
-    for i in 0..(max_y - y) {
-        if tree_heights[y + i + 1][x] >= tree_heights[y][x] {
-            return i + 1;
+        // Equal-height trees are replaced since the later tree blocks the same view
+        if let Some((_, top)) = stack.last() {
+            if *top == h {
+                stack.pop();
+            }
         }
+        stack.push((i, h));
     }
-    max_y - y
 }
 
 #[cfg(test)]
@@ -210,4 +199,35 @@ mod test {
         let solution = solve_part2(&input);
         assert_eq!(496125, solution);
     }
+
+    /// Tests the Day 8 Part 1 solver method against the example input.
+    #[test]
+    fn test_day08_part1_t001() {
+        let input = process_input_file("./input/test/day08_t001.txt");
+        let solution = solve_part1(&input);
+        assert_eq!(21, solution);
+    }
+
+    /// Tests the Day 8 Part 2 solver method against the example input, including the location of
+    /// the best tree.
+    #[test]
+    fn test_day08_part2_t001() {
+        let input = process_input_file("./input/test/day08_t001.txt");
+        let solution = solve_part2(&input);
+        assert_eq!(8, solution);
+        assert_eq!((Point2D::new(2, 3), 8), find_best_tree(&input));
+    }
+
+    /// Tests non-square grids with heights above 9 given as whitespace-separated numbers.
+    #[test]
+    fn test_day08_separated_heights() {
+        let input = parse_tree_heights("10 3 12\n4 11 5\n1 2 20\n7 30 0\n").unwrap();
+        let (visible, scenic_scores) = compute_tree_maps(&input);
+        assert_eq!(vec![true, true, true], visible[1]);
+        assert_eq!(12, solve_part1(&input));
+        assert_eq!(vec![0, 2, 0], scenic_scores[1]);
+        assert_eq!((Point2D::new(1, 1), 2), find_best_tree(&input));
+        assert!(parse_tree_heights("123\n12\n").is_err());
+        assert!(parse_tree_heights("1a3\n").is_err());
+    }
 }
@@ -1,22 +1,14 @@
 use std::collections::HashSet;
 use std::fs;
+use std::iter;
 use std::time::Instant;
 
-use aoc2022::utils::cartography::Point2D;
+use aoc2022::utils::cartography::{CompassDirection, Point2D};
 
 const PROBLEM_NAME: &str = "Rope Bridge";
 const PROBLEM_INPUT_FILE: &str = "./input/day09.txt";
 const PROBLEM_DAY: u64 = 9;
 
-/// Represents a movement in a different cardinal direction with an associated number of steps.
-#[derive(Debug)]
-enum MoveType {
-    Up,
-    Down,
-    Left,
-    Right,
-}
-
 /// Processes the AOC 2022 Day 9 input file and solves both parts of the problem. Solutions are
 /// printed to stdout.
 pub fn main() {
@@ -51,104 +43,141 @@ pub fn main() {
 }
 
 /// Processes the AOC 2022 Day 9 input file in the format required by the solver functions.
-/// Returned value is vector of tuples containing move type and number of steps.
-fn process_input_file(filename: &str) -> Vec<(MoveType, usize)> {
+/// Returned value is vector of tuples containing move direction and number of steps.
+fn process_input_file(filename: &str) -> Vec<(CompassDirection, usize)> {
     // Read contents of problem input file
     let raw_input = fs::read_to_string(filename).unwrap();
     // Process input file contents into data structure
-    let mut output: Vec<(MoveType, usize)> = vec![];
-    for line in raw_input.lines() {
+    parse_rope_moves(&raw_input).unwrap()
+}
+
+/// Parses the rope moves from the raw input. Moves can be in the cardinal directions (U, R, D, L)
+/// or diagonal (UR, DR, DL, UL, in either letter order).
+fn parse_rope_moves(raw_input: &str) -> Result<Vec<(CompassDirection, usize)>, String> {
+    let mut output: Vec<(CompassDirection, usize)> = vec![];
+    for (i, line) in raw_input.lines().enumerate() {
         let line = line.trim();
         if line.is_empty() {
             continue;
         }
-        let split = line.split(' ').collect::<Vec<&str>>();
-        let steps = split[1].parse::<usize>().unwrap();
-        match split[0] {
-            "U" => {
-                output.push((MoveType::Up, steps));
+        let (dirn, steps) = match line.split_once(' ') {
+            Some((dirn, steps)) => (dirn, steps.trim()),
+            None => return Err(format!("Day 9 - line {}: bad move \"{}\"", i + 1, line)),
+        };
+        let steps = match steps.parse::<usize>() {
+            Ok(steps) => steps,
+            Err(_) => return Err(format!("Day 9 - line {}: bad step count", i + 1)),
+        };
+        let dirn = match dirn {
+            "U" => CompassDirection::North,
+            "UR" | "RU" => CompassDirection::NorthEast,
+            "R" => CompassDirection::East,
+            "DR" | "RD" => CompassDirection::SouthEast,
+            "D" => CompassDirection::South,
+            "DL" | "LD" => CompassDirection::SouthWest,
+            "L" => CompassDirection::West,
+            "UL" | "LU" => CompassDirection::NorthWest,
+            _ => {
+                return Err(format!(
+                    "Day 9 - line {}: bad move type \"{}\"",
+                    i + 1,
+                    dirn
+                ))
             }
-            "R" => {
-                output.push((MoveType::Right, steps));
-            }
-            "D" => {
-                output.push((MoveType::Down, steps));
-            }
-            "L" => {
-                output.push((MoveType::Left, steps));
-            }
-            _ => panic!("Day 9 - bad move type!"),
-        }
+        };
+        output.push((dirn, steps));
     }
-    output
+    Ok(output)
 }
 
 /// Solves AOC 2022 Day 9 Part 1 // Calculates the number of unique locations visited by the tail of
 /// the rope (two knots).
-fn solve_part1(instructions: &[(MoveType, usize)]) -> usize {
-    process_rope_moves(instructions, 2)
+fn solve_part1(instructions: &[(CompassDirection, usize)]) -> usize {
+    process_rope_moves(instructions, 2, 1)
 }
 
 /// Solves AOC 2022 Day 9 Part 2 // Calculates the number of unique locations visited by the tail of
 /// the rope (10 knots).
-fn solve_part2(instructions: &[(MoveType, usize)]) -> usize {
-    process_rope_moves(instructions, 10)
+fn solve_part2(instructions: &[(CompassDirection, usize)]) -> usize {
+    process_rope_moves(instructions, 10, 1)
 }
 
 /// Processes the rope moves and returns the number of unique locations visited by the tail knot.
-fn process_rope_moves(instructions: &[(MoveType, usize)], rope_len: usize) -> usize {
+fn process_rope_moves(
+    instructions: &[(CompassDirection, usize)],
+    rope_len: usize,
+    slack: u64,
+) -> usize {
     if rope_len == 0 {
         return 0;
     }
-    let mut tail_locs: HashSet<Point2D> = HashSet::new();
-    let mut knots: Vec<Point2D> = vec![Point2D::new(0, 0); rope_len].to_vec();
-    tail_locs.insert(knots[rope_len - 1]);
-    for (move_type, steps) in instructions {
-        for _ in 0..*steps {
-            // Move the first knot
-            let mut new_knots: Vec<Point2D> = vec![];
-            match move_type {
-                MoveType::Up => new_knots.push(knots[0].peek_move_point(0, -1)),
-                MoveType::Down => new_knots.push(knots[0].peek_move_point(0, 1)),
-                MoveType::Left => new_knots.push(knots[0].peek_move_point(-1, 0)),
-                MoveType::Right => new_knots.push(knots[0].peek_move_point(1, 0)),
-            }
-            // Now move the following knots
-            for i in 1..rope_len {
-                let delta_x = new_knots[i - 1].x() - knots[i].x();
-                let delta_y = new_knots[i - 1].y() - knots[i].y();
-                if delta_x.abs() >= 2 || delta_y.abs() >= 2 {
-                    // Normalise delta_x
-                    let dx = {
-                        if delta_x >= 2 {
-                            1
-                        } else if delta_x <= -2 {
-                            -1
-                        } else {
-                            0
-                        }
-                    };
-                    // Normalise delta_y
-                    let dy = {
-                        if delta_y >= 2 {
-                            1
-                        } else if delta_y <= -2 {
-                            -1
-                        } else {
-                            0
-                        }
-                    };
-                    new_knots.push(knots[i].peek_move_point(delta_x - dx, delta_y - dy));
-                } else {
-                    new_knots.push(knots[i]);
-                }
+    let mut rope = Rope::new(rope_len, slack);
+    rope.simulate(instructions).for_each(drop);
+    rope.visited(rope_len - 1).len()
+}
+
+/// Represents a rope made up of knots, with the head knot first. Each knot follows the knot ahead
+/// of it once the Chebyshev distance between them exceeds the slack distance.
+struct Rope {
+    knots: Vec<Point2D>,
+    visited: Vec<HashSet<Point2D>>,
+    slack: u64,
+}
+
+impl Rope {
+    /// Creates a new rope with the given number of knots, all starting at the origin. Panics if the
+    /// rope has no knots.
+    fn new(knot_count: usize, slack: u64) -> Self {
+        assert!(knot_count > 0, "Day 9 - rope must have at least one knot!");
+        let origin = Point2D::new(0, 0);
+        Self {
+            knots: vec![origin; knot_count],
+            visited: vec![HashSet::from([origin]); knot_count],
+            slack,
+        }
+    }
+
+    /// Gets the current locations of the knots, with the head knot first.
+    fn knots(&self) -> &[Point2D] {
+        &self.knots
+    }
+
+    /// Gets the set of locations visited by the knot at the given index.
+    fn visited(&self, knot: usize) -> &HashSet<Point2D> {
+        &self.visited[knot]
+    }
+
+    /// Moves the head knot a single step in the given direction, and updates the following knots.
+    fn step(&mut self, dirn: CompassDirection) {
+        self.knots[0] = self.knots[0].check_move_in_direction(dirn);
+        self.visited[0].insert(self.knots[0]);
+        for i in 1..self.knots.len() {
+            let delta_x = self.knots[i - 1].x() - self.knots[i].x();
+            let delta_y = self.knots[i - 1].y() - self.knots[i].y();
+            // Chebyshev distance is the larger of the distances along each axis
+            if delta_x.unsigned_abs().max(delta_y.unsigned_abs()) <= self.slack {
+                // Following knots cannot move if this one has not moved
+                break;
             }
-            // Update the knot locations and insert the tail knot location into set
-            knots = new_knots;
-            tail_locs.insert(knots[rope_len - 1]);
+            self.knots[i].move_point(delta_x.signum(), delta_y.signum());
+            self.visited[i].insert(self.knots[i]);
         }
     }
-    tail_locs.len()
+
+    /// Applies the move instructions to the rope, yielding the locations of the knots after each
+    /// single step of the head knot.
+    fn simulate<'a>(
+        &'a mut self,
+        instructions: &'a [(CompassDirection, usize)],
+    ) -> impl Iterator<Item = Vec<Point2D>> + 'a {
+        instructions
+            .iter()
+            .flat_map(|(dirn, steps)| iter::repeat_n(*dirn, *steps))
+            .map(move |dirn| {
+                self.step(dirn);
+                self.knots().to_vec()
+            })
+    }
 }
 
 #[cfg(test)]
@@ -170,4 +199,47 @@ mod test {
         let solution = solve_part2(&input);
         assert_eq!(2482, solution);
     }
+
+    /// Tests the Day 9 Part 1 solver method against the first example input.
+    #[test]
+    fn test_day09_part1_t001() {
+        let input = process_input_file("./input/test/day09_t001.txt");
+        let solution = solve_part1(&input);
+        assert_eq!(13, solution);
+    }
+
+    /// Tests the Day 9 Part 2 solver method against the first and second example inputs.
+    #[test]
+    fn test_day09_part2_t001_t002() {
+        let input = process_input_file("./input/test/day09_t001.txt");
+        assert_eq!(1, solve_part2(&input));
+        let input = process_input_file("./input/test/day09_t002.txt");
+        assert_eq!(36, solve_part2(&input));
+    }
+
+    /// Tests diagonal moves, per-knot visited sets, the step iterator and a larger slack distance.
+    #[test]
+    fn test_day09_rope_variants() {
+        let input = parse_rope_moves("UR 3\nDL 1\nL 2").unwrap();
+        let mut rope = Rope::new(3, 1);
+        let steps = rope.simulate(&input).collect::<Vec<Vec<Point2D>>>();
+        assert_eq!(6, steps.len());
+        assert_eq!(
+            vec![
+                Point2D::new(3, -3),
+                Point2D::new(2, -2),
+                Point2D::new(1, -1)
+            ],
+            steps[2]
+        );
+        assert_eq!(Point2D::new(0, -2), rope.knots()[0]);
+        assert_eq!(6, rope.visited(0).len());
+        assert_eq!(4, rope.visited(1).len());
+        assert_eq!(2, rope.visited(2).len());
+        let mut slack_rope = Rope::new(2, 2);
+        slack_rope.simulate(&input).for_each(drop);
+        assert_eq!(2, slack_rope.visited(1).len());
+        assert!(parse_rope_moves("X 3").is_err());
+        assert!(parse_rope_moves("U x").is_err());
+    }
 }
@@ -1,10 +1,7 @@
-use std::collections::VecDeque;
 use std::fs;
 use std::time::Instant;
 
-use regex::Regex;
-
-use aoc2022::utils::wildlife::{Monkey, Operation};
+use aoc2022::utils::wildlife::{parse_monkeys, project_inspections, BusinessMetric, Monkey};
 
 const PROBLEM_NAME: &str = "Monkey in the Middle";
 const PROBLEM_INPUT_FILE: &str = "./input/day11.txt";
@@ -19,11 +16,11 @@ pub fn main() {
     let input_parser_timestamp = Instant::now();
     let input_parser_duration = input_parser_timestamp.duration_since(start);
     // Solve part 1
-    let p1_solution = solve_part1(&input);
+    let p1_solution = solve_part1(&input).unwrap();
     let p1_timestamp = Instant::now();
     let p1_duration = p1_timestamp.duration_since(input_parser_timestamp);
     // Solve part 2
-    let p2_solution = solve_part2(&input);
+    let p2_solution = solve_part2(&input).unwrap();
     let p2_timestamp = Instant::now();
     let p2_duration = p2_timestamp.duration_since(p1_timestamp);
     // Print results
@@ -49,91 +46,49 @@ fn process_input_file(filename: &str) -> Vec<Monkey> {
     // Read contents of problem input file
     let raw_input = fs::read_to_string(filename).unwrap();
     // Process input file contents into data structure
-    let mut output: Vec<Monkey> = vec![];
-    let regex_monkey = Regex::new(concat!(
-        r#"Monkey (\d+):%  Starting items: (.*)%  Operation: new = old (.*)%"#,
-        r#"  Test: divisible by (\d+)%    If true: throw to monkey (\d+)%"#,
-        r#"    If false: throw to monkey (\d+)"#
-    ))
-    .unwrap();
-    for split in raw_input
-        .trim()
-        .split("\n\n")
-        .map(|group| group.replace('\n', "%"))
-    {
-        let caps = regex_monkey.captures(&split).unwrap();
-        // Extract starting items
-        let items: VecDeque<u64> = caps[2]
-            .split(", ")
-            .map(|value| value.parse::<u64>().unwrap())
-            .collect::<VecDeque<u64>>();
-        // Determine operation
-        let op = {
-            if &caps[3] == "* old" {
-                Operation::Pow { value: 2 }
-            } else if caps[3].starts_with('+') {
-                let value = caps[3].split("+ ").nth(1).unwrap().parse::<u64>().unwrap();
-                Operation::Add { value }
-            } else if caps[3].starts_with('*') {
-                let value = caps[3].split("* ").nth(1).unwrap().parse::<u64>().unwrap();
-                Operation::Mult { value }
-            } else {
-                panic!("Day 11 - bad operation!");
-            }
-        };
-        let test_mod = caps[4].parse::<u64>().unwrap();
-        let true_monkey = caps[5].parse::<usize>().unwrap();
-        let false_monkey = caps[6].parse::<usize>().unwrap();
-        output.push(Monkey::new(items, op, test_mod, true_monkey, false_monkey));
-    }
-    output
+    parse_monkeys(&raw_input).unwrap()
 }
 
 /// Solves AOC 2022 Day 11 Part 1 // Calculates the resulting monkey business level after 20 rounds
 /// with worry reduction in place.
-fn solve_part1(initial_monkeys: &[Monkey]) -> u64 {
+fn solve_part1(initial_monkeys: &[Monkey]) -> Result<u128, String> {
     get_monkey_business(initial_monkeys, 20, true)
 }
 
 /// Solves AOC 2022 Day 11 Part 2 // Calculates the resulting monkey business level after 10,000
 /// rounds without worry reduction in place.
-fn solve_part2(initial_monkeys: &[Monkey]) -> u64 {
+fn solve_part2(initial_monkeys: &[Monkey]) -> Result<u128, String> {
     get_monkey_business(initial_monkeys, 10000, false)
 }
 
-/// Conducts a given number of rounds of monkey business.
-fn get_monkey_business(initial_monkeys: &[Monkey], rounds: u128, reduce_worry: bool) -> u64 {
-    let mut monkeys = initial_monkeys.to_owned();
-    let supermodulo: u64 = monkeys.iter().map(|m| m.get_divisor()).product();
-    for _ in 0..rounds {
-        // Conduct rounds
-        for i in 0..monkeys.len() {
-            // Get the items thrown by the current monkey then give them to the receiving monkey
-            let thrown_items = monkeys[i].inspect_and_throw(reduce_worry, supermodulo);
-            for (new_monkey, item) in thrown_items {
-                monkeys[new_monkey].give_item(item);
-            }
-        }
-    }
-    // Calculate the monkey business number - product of # items inspected from two busiest monkeys
-    let mut output = monkeys
-        .iter()
-        .map(|m| m.get_items_inspected())
-        .collect::<Vec<u64>>();
-    output.sort();
-    return output.iter().rev().take(2).product();
+/// Conducts a given number of rounds of monkey business, fast-forwarding through the rounds once
+/// each item is found to be in a cycle. Returns the product of the number of items inspected by the
+/// two busiest monkeys, or an error if a worry level could not be represented.
+fn get_monkey_business(
+    initial_monkeys: &[Monkey],
+    rounds: u128,
+    reduce_worry: bool,
+) -> Result<u128, String> {
+    let inspections = project_inspections(initial_monkeys, rounds, reduce_worry)?;
+    BusinessMetric::TopProduct { k: 2 }
+        .calculate(&inspections)
+        .ok_or(String::from("monkey business overflows u128"))
 }
 
 #[cfg(test)]
 mod test {
     use super::*;
+    use std::collections::HashMap;
+
+    use aoc2022::utils::numeric::BigUint;
+    use aoc2022::utils::wildlife::{MonkeyTroop, Operation, RoundSnapshot, MAX_EXACT_TRACE_ROUNDS};
 
     /// Tests the Day 11 Part 1 solver method against the actual problem solution.
     #[test]
     fn test_day11_part1_actual() {
         let input = process_input_file(PROBLEM_INPUT_FILE);
         let solution = solve_part1(&input);
-        assert_eq!(99840, solution);
+        assert_eq!(Ok(99840), solution);
     }
 
     /// Tests the Day 11 Part 2 solver method against the actual problem solution.
@@ -141,6 +96,454 @@ mod test {
     fn test_day11_part2_actual() {
         let input = process_input_file(PROBLEM_INPUT_FILE);
         let solution = solve_part2(&input);
-        assert_eq!(20683044837, solution);
+        assert_eq!(Ok(20683044837), solution);
+    }
+
+    /// Tests the Day 11 Part 1 solver method against the example input.
+    #[test]
+    fn test_day11_part1_t001() {
+        let input = process_input_file("./input/test/day11_t001.txt");
+        let solution = solve_part1(&input);
+        assert_eq!(Ok(10605), solution);
+    }
+
+    /// Tests the Day 11 Part 2 solver method against the example input.
+    #[test]
+    fn test_day11_part2_t001() {
+        let input = process_input_file("./input/test/day11_t001.txt");
+        let solution = solve_part2(&input);
+        assert_eq!(Ok(2713310158), solution);
+    }
+
+    /// Tests the parsing, display and evaluation of monkey operation expressions.
+    #[test]
+    fn test_day11_operation_expressions() {
+        let cases = [
+            ("old * old", 7, Some(49)),
+            ("old + old", 7, Some(14)),
+            ("old - 3", 7, Some(4)),
+            ("old * 2 + 1", 7, Some(15)),
+            ("old * (2 + 1)", 7, Some(21)),
+            ("old - (3 - 1)", 7, Some(5)),
+            ("old - 3", 2, None),
+            ("old * old * old", u64::MAX / 2, None),
+        ];
+        for (expr, old, expected) in cases {
+            let op = expr.parse::<Operation>().unwrap();
+            assert_eq!(expr, op.to_string());
+            assert_eq!(op, op.to_string().parse::<Operation>().unwrap());
+            assert_eq!(expected, op.evaluate(old));
+            if let (Some(exact), Some(modular)) = (expected, op.evaluate_mod(old, 13)) {
+                assert_eq!(exact % 13, modular);
+            }
+        }
+        // Subtractions that could go negative are rejected modulo the modulus, even where the exact
+        // result would be non-negative
+        let modular_cases = [
+            ("old - 3", 10, None),
+            ("old - (3 - 1)", 10, None),
+            ("old * old - old", 10, None),
+            ("old + 5 - 3", 10, Some(5)),
+            ("(old + 5) * 2 - (4 - 1)", 10, Some(6)),
+            ("old * 0 - 0", 10, Some(0)),
+        ];
+        for (expr, old, expected) in modular_cases {
+            let op = expr.parse::<Operation>().unwrap();
+            assert_eq!(expected, op.evaluate_mod(old, 7));
+        }
+        assert_eq!(
+            "old * 2",
+            "(old)*2".parse::<Operation>().unwrap().to_string()
+        );
+        assert!("old / 2".parse::<Operation>().is_err());
+        assert!("old *".parse::<Operation>().is_err());
+        assert!("(old + 1".parse::<Operation>().is_err());
+    }
+
+    /// Tests that the example notes round-trip through the notebook format, and that CRLF line
+    /// endings and irregular whitespace are accepted.
+    #[test]
+    fn test_day11_notebook_round_trip_t001() {
+        let raw_input = fs::read_to_string("./input/test/day11_t001.txt").unwrap();
+        let monkeys = parse_monkeys(&raw_input).unwrap();
+        let notes = monkeys
+            .iter()
+            .map(|m| m.to_string())
+            .collect::<Vec<String>>()
+            .join("\n\n");
+        assert_eq!(raw_input.trim(), notes);
+        let messy = raw_input.replace('\n', " \r\n\r\n").replace(": ", ":   ");
+        let reparsed = parse_monkeys(&messy).unwrap();
+        assert_eq!(monkeys.len(), reparsed.len());
+        assert_eq!(solve_part1(&monkeys), solve_part1(&reparsed));
+    }
+
+    /// Tests that notes with bad monkey ids, divisors or throw targets are rejected.
+    #[test]
+    fn test_day11_notebook_validation() {
+        let notes = |id: usize, divisor: u64, t: usize, f: usize| {
+            format!(
+                concat!(
+                    "Monkey {}:\n  Starting items: 1\n  Operation: new = old\n",
+                    "  Test: divisible by {}\n    If true: throw to monkey {}\n",
+                    "    If false: throw to monkey {}\n"
+                ),
+                id, divisor, t, f
+            )
+        };
+        let valid = format!("{}\n{}", notes(0, 2, 1, 1), notes(1, 3, 0, 0));
+        assert_eq!(2, parse_monkeys(&valid).unwrap().len());
+        assert!(parse_monkeys(&format!("{}\n{}", notes(0, 2, 1, 1), notes(2, 3, 0, 0))).is_err());
+        assert!(parse_monkeys(&format!("{}\n{}", notes(0, 0, 1, 1), notes(1, 3, 0, 0))).is_err());
+        assert!(parse_monkeys(&format!("{}\n{}", notes(0, 2, 0, 1), notes(1, 3, 0, 0))).is_err());
+        assert!(parse_monkeys(&format!("{}\n{}", notes(0, 2, 1, 2), notes(1, 3, 0, 0))).is_err());
+        assert!(parse_monkeys(&notes(0, 2, 1, 1)[..40]).is_err());
+    }
+
+    /// Tests the per-round snapshots of the example troop against the rounds described in the
+    /// problem statement.
+    #[test]
+    fn test_day11_round_snapshots_t001() {
+        let input = process_input_file("./input/test/day11_t001.txt");
+        let mut troop = MonkeyTroop::new(&input, true).unwrap();
+        let snapshots = troop
+            .rounds(20)
+            .collect::<Result<Vec<RoundSnapshot>, String>>()
+            .unwrap();
+        assert_eq!(1, snapshots[0].round());
+        assert_eq!(
+            vec![
+                vec![20, 23, 27, 26],
+                vec![2080, 25, 167, 207, 401, 1046],
+                vec![],
+                vec![]
+            ],
+            snapshots[0].items()
+        );
+        assert_eq!((0, 3, 500), snapshots[0].throws()[0]);
+        assert_eq!(vec![101, 95, 7, 105], snapshots[19].inspections());
+        assert_eq!(20, troop.round());
+        assert_eq!(
+            Some(10605),
+            troop.monkey_business(BusinessMetric::TopProduct { k: 2 })
+        );
+        assert_eq!(
+            Some(206),
+            troop.monkey_business(BusinessMetric::TopSum { k: 2 })
+        );
+        assert_eq!(
+            Some(308),
+            troop.monkey_business(BusinessMetric::TopSum { k: 4 })
+        );
+        // Products of the largest counts overflow u128 for larger k
+        let counts = [u64::MAX as u128; 3];
+        assert_eq!(
+            Some(u64::MAX as u128 * u64::MAX as u128),
+            BusinessMetric::TopProduct { k: 2 }.calculate(&counts)
+        );
+        assert_eq!(None, BusinessMetric::TopProduct { k: 3 }.calculate(&counts));
+        assert_eq!(
+            None,
+            BusinessMetric::TopSum { k: 2 }.calculate(&[u128::MAX, 1])
+        );
+    }
+
+    /// Tests the arbitrary-precision integers used to track exact worry levels.
+    #[test]
+    fn test_day11_biguint_arithmetic() {
+        let a = BigUint::from(u64::MAX);
+        let b = BigUint::from(12345678901234567890);
+        assert_eq!("18446744073709551615", a.to_string());
+        assert_eq!("36893488147419103230", (&a + &a).to_string());
+        assert_eq!(
+            "227737579107269814010216029110176642350",
+            (&a * &b).to_string()
+        );
+        assert_eq!(
+            (
+                BigUint::from(18446744073709551615 / 7),
+                18446744073709551615 % 7
+            ),
+            a.div_rem_u64(7)
+        );
+        assert_eq!(Some(a.clone()), (&a + &b).checked_sub(&b));
+        assert_eq!(None, b.checked_sub(&a));
+        assert_eq!(Some(0), (&a * &BigUint::zero()).to_u64());
+        assert_eq!(None, (&a + &a).to_u64());
+        assert!(b < a);
+    }
+
+    /// Tests that the exact big-integer mode agrees with the default u64 mode, with and without
+    /// worry reduction in place.
+    #[test]
+    fn test_day11_exact_mode_cross_check_t001() {
+        let input = process_input_file("./input/test/day11_t001.txt");
+        let exact = input
+            .iter()
+            .map(|m| m.to_exact())
+            .collect::<Vec<Monkey<BigUint>>>();
+        let mut exact_troop = MonkeyTroop::new(&exact, true).unwrap();
+        for _ in 0..20 {
+            exact_troop.play_round().unwrap();
+        }
+        assert_eq!(
+            solve_part1(&input).ok(),
+            exact_troop.monkey_business(BusinessMetric::TopProduct { k: 2 })
+        );
+        // Without worry reduction the exact levels grow quickly, so compare a limited number of
+        // rounds - item residues must match modulo the product of the divisors
+        let supermodulo = input.iter().map(|m| m.get_divisor()).product::<u64>();
+        let mut troop = MonkeyTroop::new(&input, false).unwrap();
+        let mut exact_troop = MonkeyTroop::new(&exact, false).unwrap();
+        for _ in 0..12 {
+            let throws = troop.play_round().unwrap();
+            let exact_throws = exact_troop.play_round().unwrap();
+            assert_eq!(throws.len(), exact_throws.len());
+            for ((from, to, worry), (exact_from, exact_to, exact_worry)) in
+                throws.iter().zip(exact_throws.iter())
+            {
+                assert_eq!((from, to), (exact_from, exact_to));
+                assert_eq!(*worry, exact_worry.div_rem_u64(supermodulo).1);
+            }
+            assert_eq!(troop.inspection_counts(), exact_troop.inspection_counts());
+        }
+    }
+
+    /// Tests that a worry level overflowing u64 with worry reduction in place is reported as an
+    /// error, and that the exact mode tracks the true worry level.
+    #[test]
+    fn test_day11_worry_overflow() {
+        let notes = concat!(
+            "Monkey 0:\n  Starting items: 4294967296\n  Operation: new = old * old\n",
+            "  Test: divisible by 2\n    If true: throw to monkey 1\n",
+            "    If false: throw to monkey 1\n\n",
+            "Monkey 1:\n  Starting items:\n  Operation: new = old * 3\n",
+            "  Test: divisible by 3\n    If true: throw to monkey 0\n",
+            "    If false: throw to monkey 0\n"
+        );
+        let monkeys = parse_monkeys(notes).unwrap();
+        let result = get_monkey_business(&monkeys, 1, true);
+        assert!(result.unwrap_err().contains("monkey 0"));
+        let exact = monkeys
+            .iter()
+            .map(|m| m.to_exact())
+            .collect::<Vec<Monkey<BigUint>>>();
+        let mut troop = MonkeyTroop::new(&exact, true).unwrap();
+        let throws = troop.play_round().unwrap();
+        assert_eq!("6148914691236517205", throws[0].2.to_string());
+        assert_eq!("6148914691236517205", throws[1].2.to_string());
+        assert_eq!(
+            Ok(4),
+            MonkeyTroop::new(&monkeys, false)
+                .unwrap()
+                .play_round()
+                .map(|t| t[0].2)
+        );
+    }
+
+    /// Tests that a subtraction that could go negative is reported as an error when worry levels are
+    /// kept modulo the supermodulo, while the exact mode evaluates it.
+    #[test]
+    fn test_day11_modular_subtraction() {
+        let notes = concat!(
+            "Monkey 0:\n  Starting items: 10\n  Operation: new = old - 3\n",
+            "  Test: divisible by 7\n    If true: throw to monkey 1\n",
+            "    If false: throw to monkey 1\n\n",
+            "Monkey 1:\n  Starting items:\n  Operation: new = old + 3\n",
+            "  Test: divisible by 5\n    If true: throw to monkey 0\n",
+            "    If false: throw to monkey 0\n"
+        );
+        let monkeys = parse_monkeys(notes).unwrap();
+        let mut troop = MonkeyTroop::new(&monkeys, false).unwrap();
+        assert!(troop
+            .play_round()
+            .unwrap_err()
+            .contains("could go negative"));
+        let exact = monkeys
+            .iter()
+            .map(|m| m.to_exact())
+            .collect::<Vec<Monkey<BigUint>>>();
+        let mut exact_troop = MonkeyTroop::new(&exact, false).unwrap();
+        let throws = exact_troop.play_round().unwrap();
+        assert_eq!("7", throws[0].2.to_string());
+    }
+
+    /// Tests that a monkey whose second item overflows keeps all of its items without counting any
+    /// inspections, and that the items thrown earlier in the round are still delivered.
+    #[test]
+    fn test_day11_worry_overflow_second_item() {
+        let notes = concat!(
+            "Monkey 0:\n  Starting items: 5\n  Operation: new = old + 1\n",
+            "  Test: divisible by 2\n    If true: throw to monkey 1\n",
+            "    If false: throw to monkey 1\n\n",
+            "Monkey 1:\n  Starting items: 2, 4294967296\n  Operation: new = old * old\n",
+            "  Test: divisible by 3\n    If true: throw to monkey 0\n",
+            "    If false: throw to monkey 0\n"
+        );
+        let monkeys = parse_monkeys(notes).unwrap();
+        let mut troop = MonkeyTroop::new(&monkeys, true).unwrap();
+        assert!(troop.play_round().unwrap_err().contains("monkey 1"));
+        assert_eq!(vec![1, 0], troop.inspection_counts());
+        let items = troop
+            .monkeys()
+            .iter()
+            .map(|m| m.get_items().iter().copied().collect())
+            .collect::<Vec<Vec<u64>>>();
+        assert_eq!(vec![vec![], vec![2, 4294967296, 2]], items);
+    }
+
+    /// Tests that the projected inspection counts match a full simulation of the example troop,
+    /// with and without worry reduction in place.
+    #[test]
+    fn test_day11_projected_inspections_t001() {
+        let input = process_input_file("./input/test/day11_t001.txt");
+        for (rounds, reduce_worry) in [(1, false), (20, true), (60, true), (1000, false)] {
+            let mut troop = MonkeyTroop::new(&input, reduce_worry).unwrap();
+            for _ in 0..rounds {
+                troop.play_round().unwrap();
+            }
+            let simulated = troop
+                .inspection_counts()
+                .iter()
+                .map(|count| *count as u128)
+                .collect::<Vec<u128>>();
+            assert_eq!(
+                Ok(simulated),
+                project_inspections(&input, rounds, reduce_worry)
+            );
+        }
+        assert_eq!(Ok(vec![0, 0, 0, 0]), project_inspections(&input, 0, false));
+    }
+
+    /// Tests that the projection fast-forwards to a very large number of rounds, with the
+    /// inspections matching an exact extrapolation from a cycle of the troop state found by
+    /// simulating a small troop.
+    #[test]
+    fn test_day11_projection_large_rounds() {
+        let notes = concat!(
+            "Monkey 0:\n  Starting items: 1, 4\n  Operation: new = old * 2 + 1\n",
+            "  Test: divisible by 2\n    If true: throw to monkey 2\n",
+            "    If false: throw to monkey 1\n\n",
+            "Monkey 1:\n  Starting items: 3\n  Operation: new = old + 4\n",
+            "  Test: divisible by 3\n    If true: throw to monkey 0\n",
+            "    If false: throw to monkey 2\n\n",
+            "Monkey 2:\n  Starting items: 5, 2\n  Operation: new = old * old\n",
+            "  Test: divisible by 5\n    If true: throw to monkey 1\n",
+            "    If false: throw to monkey 0\n"
+        );
+        let monkeys = parse_monkeys(notes).unwrap();
+        // The items held by each monkey determine the inspections in all later rounds, so the
+        // inspections repeat from the first round where the held items repeat
+        let held_items = |troop: &MonkeyTroop| {
+            troop
+                .monkeys()
+                .iter()
+                .map(|m| {
+                    let mut items = m.get_items().iter().copied().collect::<Vec<u64>>();
+                    items.sort();
+                    items
+                })
+                .collect::<Vec<Vec<u64>>>()
+        };
+        let mut troop = MonkeyTroop::new(&monkeys, false).unwrap();
+        let mut seen: HashMap<Vec<Vec<u64>>, usize> = HashMap::new();
+        let mut counts: Vec<Vec<u64>> = vec![];
+        let cycle_start = loop {
+            counts.push(troop.inspection_counts());
+            if let Some(start) = seen.insert(held_items(&troop), troop.round()) {
+                break start;
+            }
+            assert!(troop.round() < 10000);
+            troop.play_round().unwrap();
+        };
+        let cycle_len = troop.round() - cycle_start;
+        let total_rounds: u128 = 1_000_000_000_000;
+        let cycles = (total_rounds - cycle_start as u128) / cycle_len as u128;
+        let remainder = ((total_rounds - cycle_start as u128) % cycle_len as u128) as usize;
+        let expected = (0..monkeys.len())
+            .map(|id| {
+                let per_cycle = counts[cycle_start + cycle_len][id] - counts[cycle_start][id];
+                counts[cycle_start + remainder][id] as u128 + cycles * per_cycle as u128
+            })
+            .collect::<Vec<u128>>();
+        assert_eq!(
+            Ok(expected.clone()),
+            project_inspections(&monkeys, total_rounds, false)
+        );
+        assert_eq!(
+            BusinessMetric::TopProduct { k: 2 }.calculate(&expected),
+            get_monkey_business(&monkeys, total_rounds, false).ok()
+        );
+    }
+
+    /// Tests that the projection with worry reduction in place gives up on an item whose exact worry
+    /// level keeps growing, once more rounds are requested than it will trace.
+    #[test]
+    fn test_day11_projection_exact_round_limit() {
+        let notes = concat!(
+            "Monkey 0:\n  Starting items: 1\n  Operation: new = old * 3 + 3\n",
+            "  Test: divisible by 7\n    If true: throw to monkey 1\n",
+            "    If false: throw to monkey 1\n\n",
+            "Monkey 1:\n  Starting items:\n  Operation: new = old * 3\n",
+            "  Test: divisible by 11\n    If true: throw to monkey 0\n",
+            "    If false: throw to monkey 0\n"
+        );
+        let monkeys = parse_monkeys(notes).unwrap();
+        let rounds = MAX_EXACT_TRACE_ROUNDS as u128;
+        assert_eq!(
+            Ok(vec![rounds, rounds]),
+            project_inspections(&monkeys, rounds, true)
+        );
+        assert!(project_inspections(&monkeys, rounds + 1, true)
+            .unwrap_err()
+            .contains("did not cycle"));
+        assert_eq!(
+            Ok(vec![1_000_000_000_000, 1_000_000_000_000]),
+            project_inspections(&monkeys, 1_000_000_000_000, false)
+        );
+    }
+
+    /// Tests that a troop whose divisors have a supermodulo overflowing u64 is reported as an error
+    /// without worry reduction, and that the supermodulo is not needed otherwise.
+    #[test]
+    fn test_day11_supermodulo_overflow() {
+        let primes = [101, 103, 107, 109, 113, 127, 131, 137, 139, 149];
+        let notes = primes
+            .iter()
+            .enumerate()
+            .map(|(id, divisor)| {
+                format!(
+                    concat!(
+                        "Monkey {}:\n  Starting items: 79\n  Operation: new = old + 19\n",
+                        "  Test: divisible by {}\n    If true: throw to monkey {}\n",
+                        "    If false: throw to monkey {}\n"
+                    ),
+                    id,
+                    divisor,
+                    (id + 1) % primes.len(),
+                    (id + 2) % primes.len()
+                )
+            })
+            .collect::<Vec<String>>()
+            .join("\n");
+        let monkeys = parse_monkeys(&notes).unwrap();
+        assert!(MonkeyTroop::new(&monkeys, false)
+            .unwrap_err()
+            .contains("overflows"));
+        assert!(project_inspections(&monkeys, 10, false).is_err());
+        assert!(get_monkey_business(&monkeys, 10, false).is_err());
+        assert!(MonkeyTroop::new(&monkeys, true).is_ok());
+        assert!(get_monkey_business(&monkeys, 10, true).is_ok());
+        let exact = monkeys
+            .iter()
+            .map(|m| m.to_exact())
+            .collect::<Vec<Monkey<BigUint>>>();
+        let mut exact_troop = MonkeyTroop::new(&exact, false).unwrap();
+        for _ in 0..5 {
+            exact_troop.play_round().unwrap();
+        }
+        assert_eq!(5, exact_troop.round());
+        let items = exact_troop.monkeys().iter().map(|m| m.get_items().len());
+        assert_eq!(primes.len(), items.sum::<usize>());
     }
 }
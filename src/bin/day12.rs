@@ -1,8 +1,7 @@
-use std::collections::{HashMap, HashSet, VecDeque};
 use std::fs;
 use std::time::Instant;
 
-use aoc2022::utils::cartography::Point2D;
+use aoc2022::utils::terrain::{ClimbRule, DistanceField, Heightmap};
 
 const PROBLEM_NAME: &str = "Hill Climbing Algorithm";
 const PROBLEM_INPUT_FILE: &str = "./input/day12.txt";
@@ -42,141 +41,42 @@ pub fn main() {
 }
 
 /// Processes the AOC 2022 Day 12 input file in the format required by the solver functions.
-/// Returned value is tuple containing the heightmap, start point and end point.
-fn process_input_file(filename: &str) -> (HashMap<Point2D, i64>, Point2D, Point2D) {
+/// Returned value is the heightmap given in the input file.
+fn process_input_file(filename: &str) -> Heightmap {
     // Read contents of problem input file
     let raw_input = fs::read_to_string(filename).unwrap();
     // Process input file contents into data structure
-    let mut start: Option<Point2D> = None;
-    let mut end: Option<Point2D> = None;
-    let mut heightmap: HashMap<Point2D, i64> = HashMap::new();
-    let mut y = 0;
-    for line in raw_input.trim().lines() {
-        let line = line.trim();
-        if line.is_empty() {
-            continue;
-        }
-        // Process the line of the heightmap
-        for (x, chr) in line.chars().enumerate() {
-            if chr == 'S' {
-                start = Some(Point2D::new(x as i64, y));
-                heightmap.insert(start.unwrap(), 0);
-            } else if chr == 'E' {
-                end = Some(Point2D::new(x as i64, y));
-                heightmap.insert(end.unwrap(), 25);
-            } else if chr.is_ascii_lowercase() {
-                let height = (chr as i64) - ('a' as i64);
-                heightmap.insert(Point2D::new(x as i64, y), height);
-            } else {
-                panic!("Day 12 - bad char in input file!");
-            }
-        }
-        y += 1;
+    match Heightmap::parse(&raw_input) {
+        Ok(heightmap) => heightmap,
+        Err(e) => panic!("Day 12 - {}", e),
     }
-    (heightmap, start.unwrap(), end.unwrap())
 }
 
 /// Solves AOC 2022 Day 12 Part 1 // Determines the minimum number of steps needed to reach the end
 /// point from the start point.
-fn solve_part1(problem_input: &(HashMap<Point2D, i64>, Point2D, Point2D)) -> u64 {
-    let (heightmap, start, end) = problem_input;
-    get_min_steps_to_end(heightmap, start, end)
-}
-
-/// Solves AOC 2022 Day 12 Part 2 // Determines the minimum number of steps needed to reach the end
-/// point from a starting point with elevation 0.
-fn solve_part2(problem_input: &(HashMap<Point2D, i64>, Point2D, Point2D)) -> u64 {
-    let (heightmap, _, end) = problem_input;
-    get_min_steps_from_elevation0_to_end(heightmap, end)
-}
-
-/// Determines the minimum number of steps needed to reach the end point from the start point.
-fn get_min_steps_to_end(heightmap: &HashMap<Point2D, i64>, start: &Point2D, end: &Point2D) -> u64 {
-    let mut visit_queue: VecDeque<(u64, Point2D)> = VecDeque::new();
-    visit_queue.push_back((0, *start));
-    let mut visited: HashSet<Point2D> = HashSet::new();
-    visited.insert(*start);
-    while !visit_queue.is_empty() {
-        // Get the current point to visit
-        let (steps, current_loc) = visit_queue.pop_front().unwrap();
-        if current_loc == *end {
-            return steps;
-        }
-        // Add the next points to visit
-        for valid_point in get_next_valid_points(heightmap, &current_loc, false) {
-            if !visited.contains(&valid_point) {
-                visit_queue.push_back((steps + 1, valid_point));
-                visited.insert(valid_point);
-            }
-        }
+fn solve_part1(heightmap: &Heightmap) -> u64 {
+    if let Some(route) =
+        heightmap.find_route(&heightmap.start(), &heightmap.end(), &ClimbRule::default())
+    {
+        return route.len() as u64 - 1;
     }
-    // Should have reached the end point already, so getting here indicates an error somewhere
     panic!("Day 12 Part 1 - did not reach the end point!");
 }
 
-/// Determines the minimum number of steps needed to reach a point with elevation 0 from the given
-/// starting point.
-fn get_min_steps_from_elevation0_to_end(heightmap: &HashMap<Point2D, i64>, start: &Point2D) -> u64 {
-    let mut visit_queue: VecDeque<(u64, Point2D)> = VecDeque::new();
-    visit_queue.push_back((0, *start));
-    let mut visited: HashSet<Point2D> = HashSet::new();
-    visited.insert(*start);
-    while !visit_queue.is_empty() {
-        // Get the current point to visit
-        let (steps, current_loc) = visit_queue.pop_front().unwrap();
-        if *heightmap.get(&current_loc).unwrap() == 0 {
-            return steps;
-        }
-        // Add the next points to visit
-        for valid_point in get_next_valid_points(heightmap, &current_loc, true) {
-            if !visited.contains(&valid_point) {
-                visit_queue.push_back((steps + 1, valid_point));
-                visited.insert(valid_point);
-            }
-        }
+/// Solves AOC 2022 Day 12 Part 2 // Determines the minimum number of steps needed to reach the end
+/// point from a starting point with elevation 0.
+fn solve_part2(heightmap: &Heightmap) -> u64 {
+    let field = DistanceField::new(heightmap, &heightmap.end(), &ClimbRule::default());
+    if let Some((_, steps)) = field.best_start(0) {
+        return steps;
     }
-    // Should have reached the end point already, so getting here indicates an error somewhere
     panic!("Day 12 Part 2 - did not reach the end point!");
 }
 
-/// Gets the next valid points to visit from the current point.
-fn get_next_valid_points(
-    heightmap: &HashMap<Point2D, i64>,
-    loc: &Point2D,
-    reverse_course: bool,
-) -> Vec<Point2D> {
-    let mut valid_points: Vec<Point2D> = vec![];
-    // Check the points to the left, up, right and down directions
-    for (delta_x, delta_y) in &[(1, 0), (-1, 0), (0, 1), (0, -1)] {
-        let check_loc = loc.peek_move_point(*delta_x, *delta_y);
-        // Determine the left and right points so elevation check is carried out correctly
-        let left = {
-            if reverse_course {
-                &check_loc
-            } else {
-                loc
-            }
-        };
-        let right = {
-            if reverse_course {
-                loc
-            } else {
-                &check_loc
-            }
-        };
-        // Check if the checked location is a valid move from the current location
-        if heightmap.contains_key(&check_loc)
-            && (heightmap.get(right).unwrap() - heightmap.get(left).unwrap()) <= 1
-        {
-            valid_points.push(check_loc);
-        }
-    }
-    valid_points
-}
-
 #[cfg(test)]
 mod test {
     use super::*;
+    use aoc2022::utils::terrain::render_route;
 
     /// Tests the Day 12 Part 1 solver method against the actual problem solution.
     #[test]
@@ -193,4 +93,74 @@ mod test {
         let solution = solve_part2(&input);
         assert_eq!(345, solution);
     }
+
+    /// Tests the Day 12 Part 1 solver method against the example input.
+    #[test]
+    fn test_day12_part1_t001() {
+        let input = process_input_file("./input/test/day12_t001.txt");
+        let solution = solve_part1(&input);
+        assert_eq!(31, solution);
+    }
+
+    /// Tests the Day 12 Part 2 solver method against the example input.
+    #[test]
+    fn test_day12_part2_t001() {
+        let input = process_input_file("./input/test/day12_t001.txt");
+        let solution = solve_part2(&input);
+        assert_eq!(29, solution);
+    }
+
+    /// Tests that the route found for the example input is valid and is rendered with an arrow at
+    /// each step.
+    #[test]
+    fn test_day12_route_rendering_t001() {
+        let input = process_input_file("./input/test/day12_t001.txt");
+        let rule = ClimbRule::default();
+        let route = input
+            .find_route(&input.start(), &input.end(), &rule)
+            .unwrap();
+        assert_eq!(32, route.len());
+        assert_eq!(Some(&input.start()), route.first());
+        assert_eq!(Some(&input.end()), route.last());
+        for step in route.windows(2) {
+            assert!(input.next_points(&step[0], &rule).contains(&step[1]));
+        }
+        let rendering = render_route(&input, &route);
+        assert_eq!(5, rendering.lines().count());
+        assert!(rendering.lines().all(|line| line.len() == 8));
+        assert_eq!(
+            31,
+            rendering.chars().filter(|c| "<>^v".contains(*c)).count()
+        );
+        assert_eq!(Some(23), rendering.find('E'));
+    }
+
+    /// Tests custom climb rules, unreachable goals and the distance field for the example input.
+    #[test]
+    fn test_day12_climb_rules_t001() {
+        let input = process_input_file("./input/test/day12_t001.txt");
+        let flat = ClimbRule::new(Some(0), None);
+        assert_eq!(None, input.find_route(&input.start(), &input.end(), &flat));
+        let steep = ClimbRule::new(None, None);
+        assert_eq!(
+            Some(input.start().calculate_manhattan_distance(&input.end()) as usize + 1),
+            input
+                .find_route(&input.start(), &input.end(), &steep)
+                .map(|route| route.len())
+        );
+        let gentle = ClimbRule::new(Some(1), Some(1));
+        assert!(gentle.allows(3, 4) && gentle.allows(4, 3) && !gentle.allows(5, 3));
+        let field = DistanceField::new(&input, &input.end(), &ClimbRule::default());
+        assert_eq!(Some(31), field.distance(&input.start()));
+        assert_eq!(Some(0), field.distance(&input.end()));
+        assert_eq!(Some((input.end(), 0)), field.best_start(25));
+        assert_eq!(Some(29), field.best_start(0).map(|(_, steps)| steps));
+        assert_eq!(
+            None,
+            DistanceField::new(&input, &input.end(), &flat).best_start(0)
+        );
+        assert!(Heightmap::parse("SabE\nab").is_err());
+        assert!(Heightmap::parse("Sab1E").is_err());
+        assert!(Heightmap::parse("abcE").is_err());
+    }
 }
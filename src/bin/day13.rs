@@ -1,21 +1,21 @@
-use std::cmp::Ordering;
+use std::env;
 use std::fs;
 use std::time::Instant;
 
-use lazy_static::lazy_static;
-use regex::Regex;
+use aoc2022::utils::signal::{explain_comparison, Packet};
 
 const PROBLEM_NAME: &str = "Distress Signal";
 const PROBLEM_INPUT_FILE: &str = "./input/day13.txt";
 const PROBLEM_DAY: u64 = 13;
 
-lazy_static! {
-    static ref REGEX_LINE: Regex = Regex::new(r"(\[|\]|\d+)").unwrap();
-}
-
 /// Processes the AOC 2022 Day 13 input file and solves both parts of the problem. Solutions are
-/// printed to stdout.
+/// printed to stdout. The comparison of packet pairs can be explained by giving their indices with
+/// the "--explain" option (e.g. "--explain 2 --explain 5").
 pub fn main() {
+    let explain_pairs = match parse_explain_args(&env::args().skip(1).collect::<Vec<String>>()) {
+        Ok(pairs) => pairs,
+        Err(e) => panic!("Day 13 - {}", e),
+    };
     let start = Instant::now();
     // Input processing
     let input = process_input_file(PROBLEM_INPUT_FILE);
@@ -34,6 +34,12 @@ pub fn main() {
     println!("AOC 2022 Day {} - \"{}\"", PROBLEM_DAY, PROBLEM_NAME);
     println!("[+] Part 1: {}", p1_solution);
     println!("[+] Part 2: {}", p2_solution);
+    for pair in explain_pairs {
+        match get_pair_explanation(&input, pair) {
+            Ok(explanation) => println!("== Pair {} ==\n{}", pair, explanation),
+            Err(e) => println!("[!] {}", e),
+        }
+    }
     println!("~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~");
     println!("Execution times:");
     println!("[+] Input:  {:.2?}", input_parser_duration);
@@ -47,142 +53,107 @@ pub fn main() {
 }
 
 /// Processes the AOC 2022 Day 13 input file in the format required by the solver functions.
-/// Returned value is vector of string pairs given in the input file.
-fn process_input_file(filename: &str) -> Vec<(String, String)> {
+/// Returned value is vector of packet pairs given in the input file.
+fn process_input_file(filename: &str) -> Vec<(Packet, Packet)> {
     // Read contents of problem input file
     let raw_input = fs::read_to_string(filename).unwrap();
     // Process input file contents into data structure
-    let mut output: Vec<(String, String)> = vec![];
-    for pair in raw_input.trim().split("\n\n") {
-        let pair_strings = pair.lines().collect::<Vec<&str>>();
-        output.push((pair_strings[0].to_string(), pair_strings[1].to_string()));
+    match parse_packet_pairs(&raw_input) {
+        Ok(pairs) => pairs,
+        Err(e) => panic!("Day 13 - {}", e),
     }
-    output
 }
 
-/// Solves AOC 2022 Day 13 Part 1 // Returns the sum of the pair indices for the pairs that are in
-/// the correct order.
-fn solve_part1(input: &[(String, String)]) -> usize {
-    let mut index_sum = 0;
-    // Check which packet pairs are in the right order
-    for (i, (left, right)) in input.iter().enumerate() {
-        if compare_left_and_right_packets(left, right) {
-            index_sum += i + 1;
+/// Parses the packet pairs from the distress signal, with pairs separated by blank lines.
+fn parse_packet_pairs(s: &str) -> Result<Vec<(Packet, Packet)>, String> {
+    let mut lines: Vec<(usize, &str)> = vec![];
+    for (i, line) in s.lines().enumerate() {
+        let line = line.trim();
+        if !line.is_empty() {
+            lines.push((i + 1, line));
         }
     }
-    index_sum
+    if !lines.len().is_multiple_of(2) {
+        return Err(String::from("packets do not form complete pairs"));
+    }
+    let mut pairs: Vec<(Packet, Packet)> = vec![];
+    for pair in lines.chunks(2) {
+        let mut packets = pair.iter().map(|(line_num, line)| {
+            line.parse::<Packet>()
+                .map_err(|e| format!("line {}: {}", line_num, e))
+        });
+        pairs.push((packets.next().unwrap()?, packets.next().unwrap()?));
+    }
+    Ok(pairs)
+}
+
+/// Solves AOC 2022 Day 13 Part 1 // Returns the sum of the pair indices for the pairs that are in
+/// the correct order.
+fn solve_part1(input: &[(Packet, Packet)]) -> usize {
+    input
+        .iter()
+        .enumerate()
+        .filter(|(_, (left, right))| left < right)
+        .map(|(i, _)| i + 1)
+        .sum()
 }
 
 /// Solves AOC 2022 Day 13 Part 2 // Determines the decoder key for the distress signal.
-fn solve_part2(input: &[(String, String)]) -> usize {
-    // Add all packets into the vector
-    let mut packets: Vec<String> = vec![];
+fn solve_part2(input: &[(Packet, Packet)]) -> usize {
+    // Add all packets into the vector, including the divider packets
+    let mut packets: Vec<Packet> = vec![];
     for (left, right) in input {
-        packets.push(left.to_string());
-        packets.push(right.to_string());
-    }
-    // Add the divider packets
-    packets.push(String::from("[[2]]"));
-    packets.push(String::from("[[6]]"));
-    // Sort the packets
-    packets.sort_by(|left, right| {
-        if compare_left_and_right_packets(left, right) {
-            Ordering::Less
-        } else if left == right {
-            Ordering::Equal
-        } else {
-            Ordering::Greater
-        }
-    });
-    // Get the indices of the divider packets
-    let mut index2: Option<usize> = None;
-    let mut index6: Option<usize> = None;
-    for (i, packet) in packets.iter().enumerate() {
-        if packet == "[[2]]" {
-            index2 = Some(i + 1);
-        } else if packet == "[[6]]" {
-            index6 = Some(i + 1);
-        }
-        if index2.is_some() && index6.is_some() {
-            break;
-        }
-    }
-    if index2.is_none() || index6.is_none() {
-        panic!("Day 13 Part 2 - could not find both divider packets in sorted vector!");
+        packets.push(left.clone());
+        packets.push(right.clone());
     }
-    index2.unwrap() * index6.unwrap()
+    let dividers = [Packet::divider(2), Packet::divider(6)];
+    packets.extend(dividers.iter().cloned());
+    packets.sort();
+    // Get the indices of the divider packets, with each divider placed before any packets equal to
+    // it
+    dividers
+        .iter()
+        .map(|divider| packets.partition_point(|p| p < divider) + 1)
+        .product()
 }
 
-/// Compares the left and right packets, represented by vector of their tokens. Returns true if the
-/// packets are in the right order. Otherwise, returns false.
-fn compare_left_and_right_packets(left_packet: &str, right_packet: &str) -> bool {
-    // Initialise cursors
-    let mut c_left: usize = 0;
-    let mut c_right: usize = 0;
-    // Tokenise packets
-    let mut left = REGEX_LINE
-        .find_iter(left_packet)
-        .map(|elem| elem.as_str().to_string())
-        .collect::<Vec<String>>();
-    let mut right = REGEX_LINE
-        .find_iter(right_packet)
-        .map(|elem| elem.as_str().to_string())
-        .collect::<Vec<String>>();
-    loop {
-        // Move out of end of list element
-        loop {
-            // Check bounds
-            if c_left >= left.len() {
-                // left runs out of elements first
-                return true;
-            } else if c_right >= right.len() {
-                // right runs out of elements first
-                return false;
-            }
-            // Check that both left and right are not at the end of a list
-            if left[c_left] != "]" && right[c_right] != "]" {
-                break;
-            }
-            if left[c_left] == "]" && right[c_right] != "]" {
-                // left has reached end of a list before right
-                return true;
-            } else if left[c_left] != "]" && right[c_right] == "]" {
-                // right has reached end of a list before left
-                return false;
-            } else {
-                // Both left and right at end of list with same number of elements
-                c_left += 1;
-                c_right += 1;
-            }
+/// Parses the runner arguments, which can give the indices of packet pairs to explain with the
+/// "--explain" option. Returned value is vector of the pair indices in the order given.
+fn parse_explain_args(args: &[String]) -> Result<Vec<usize>, String> {
+    let mut pairs: Vec<usize> = vec![];
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        if arg != "--explain" {
+            return Err(format!("unrecognised argument \"{}\"", arg));
         }
-        // Check if current tokens are both lists, both integers or a list and an integer
-        let left_result = left[c_left].parse::<u64>();
-        let right_result = right[c_right].parse::<u64>();
-        if right_result.is_ok() && left_result.is_err() {
-            // Replace current token of right with list
-            right.insert(c_right, String::from("["));
-            right.insert(c_right + 2, String::from("]"));
-        } else if left_result.is_ok() && right_result.is_err() {
-            // Replace current token of left with left
-            left.insert(c_left, String::from("["));
-            left.insert(c_left + 2, String::from("]"));
-        } else if let (Ok(left_val), Ok(right_val)) = (left_result, right_result) {
-            // Check if integers in order
-            match left_val.cmp(&right_val) {
-                Ordering::Less => return true,
-                Ordering::Equal => (),
-                Ordering::Greater => return false,
-            }
+        match args.next().map(|index| index.parse::<usize>()) {
+            Some(Ok(index)) => pairs.push(index),
+            _ => return Err(String::from("--explain requires a pair index")),
         }
-        // Case where both left and right are open list token not checked specifically
-        c_left += 1;
-        c_right += 1;
     }
+    Ok(pairs)
+}
+
+/// Explains the comparison of the packets in the pair with the given index (starting from 1).
+fn get_pair_explanation(input: &[(Packet, Packet)], pair: usize) -> Result<String, String> {
+    if pair == 0 || pair > input.len() {
+        return Err(format!(
+            "pair index {} is out of range (1 to {})",
+            pair,
+            input.len()
+        ));
+    }
+    let (left, right) = &input[pair - 1];
+    Ok(explain_comparison(left, right))
 }
 
 #[cfg(test)]
 mod test {
     use super::*;
+    use std::path::Path;
+
+    use aoc2022::utils::json::JsonValue;
+    use aoc2022::utils::signal::{load_packets_json, parse_packets_json};
 
     /// Tests the Day 13 Part 1 solver method against the actual problem solution.
     #[test]
@@ -199,4 +170,220 @@ mod test {
         let solution = solve_part2(&input);
         assert_eq!(24805, solution);
     }
+
+    /// Tests the Day 13 Part 1 solver method against the example input.
+    #[test]
+    fn test_day13_part1_t001() {
+        let input = process_input_file("./input/test/day13_t001.txt");
+        let solution = solve_part1(&input);
+        assert_eq!(13, solution);
+    }
+
+    /// Tests the Day 13 Part 2 solver method against the example input.
+    #[test]
+    fn test_day13_part2_t001() {
+        let input = process_input_file("./input/test/day13_t001.txt");
+        let solution = solve_part2(&input);
+        assert_eq!(140, solution);
+    }
+
+    /// Tests the Day 13 Part 2 solver method with packets that compare equal to the divider packets.
+    #[test]
+    fn test_day13_part2_packets_equal_to_dividers() {
+        let p = |s: &str| s.parse::<Packet>().unwrap();
+        let input = [(p("[2]"), p("[1]")), (p("[[[6]]]"), p("[[2]]"))];
+        // Only [1] comes before the [[2]] divider, and [1], [2], [[2]] and the [[2]] divider come
+        // before the [[6]] divider, with [[[6]]] after it
+        assert_eq!(2 * 5, solve_part2(&input));
+    }
+
+    /// Tests that packets round-trip through their display format, and that the ordering treats an
+    /// integer as equal to the list containing only that integer.
+    #[test]
+    fn test_day13_packet_ordering() {
+        let p = |s: &str| s.parse::<Packet>().unwrap();
+        for s in [
+            "[]",
+            "[[]]",
+            "7",
+            "[1,[2,[3,[4,[5,6,7]]]],8,9]",
+            "[[4,4],4,4,4]",
+        ] {
+            assert_eq!(s, p(s).to_string());
+        }
+        assert_eq!("[1,[2,3]]", p(" [ 1 , [2,3]] ").to_string());
+        assert_eq!(p("[1]"), p("1"));
+        assert_eq!(p("[[[3]]]"), p("[3]"));
+        assert!(p("[]") < p("[[]]"));
+        assert!(p("[[]]") < p("[[[]]]"));
+        assert!(p("[2,3,4]") < p("4"));
+        assert!(p("[[8,7,6]]") < p("[9]"));
+        assert!(p("[7,7,7]") < p("[7,7,7,7]"));
+        assert_eq!(Ok(0), [p("[1]"), p("[2]")].binary_search(&p("1")));
+    }
+
+    /// Tests that packets with syntax errors are rejected.
+    #[test]
+    fn test_day13_packet_syntax_errors() {
+        for s in [
+            "",
+            "[1,2",
+            "[1,,2]",
+            "[1 2]",
+            "[1],",
+            "[a]",
+            "[-1]",
+            "[99999999999999999999]",
+        ] {
+            assert!(s.parse::<Packet>().is_err(), "{}", s);
+        }
+        assert!(parse_packet_pairs("[1]\n[2]\n\n[3]").is_err());
+        let err = parse_packet_pairs("[1]\n[2]\n\n[3]\n[4}").unwrap_err();
+        assert!(err.starts_with("line 5:"));
+    }
+
+    /// Tests that the example packets round-trip through a pretty-printed JSON document.
+    #[test]
+    fn test_day13_json_round_trip_t001() {
+        let input = process_input_file("./input/test/day13_t001.txt");
+        let packets = input
+            .iter()
+            .flat_map(|(left, right)| [left.clone(), right.clone()])
+            .collect::<Vec<Packet>>();
+        let document = JsonValue::Array {
+            items: packets.iter().map(JsonValue::from).collect(),
+        };
+        let imported = parse_packets_json(&document.to_pretty_string()).unwrap();
+        assert_eq!(
+            packets
+                .iter()
+                .map(|p| p.to_string())
+                .collect::<Vec<String>>(),
+            imported
+                .iter()
+                .map(|p| p.to_string())
+                .collect::<Vec<String>>()
+        );
+        let packet = "[1,[2],[]]".parse::<Packet>().unwrap();
+        assert_eq!(
+            "[\n  1,\n  [\n    2\n  ],\n  []\n]",
+            JsonValue::from(&packet).to_pretty_string()
+        );
+        assert_eq!("[1,[2],[]]", JsonValue::from(&packet).to_string());
+    }
+
+    /// Tests the import of packet comparison edge cases from a JSON file.
+    #[test]
+    fn test_day13_json_import_t002() {
+        let packets = load_packets_json(Path::new("./input/test/day13_t002.json")).unwrap();
+        assert_eq!(8, packets.len());
+        assert_eq!(packets[0], packets[1]);
+        assert!(packets[2] > packets[3]);
+        assert_eq!(packets[4], packets[5]);
+        assert!(packets[6] < packets[7]);
+        assert!(load_packets_json(Path::new("./input/test/missing.json")).is_err());
+    }
+
+    /// Tests that JSON documents with content not allowed in packets are rejected.
+    #[test]
+    fn test_day13_json_rejects_non_integers() {
+        let err = parse_packets_json("[[1], [2, [1.5]]]").unwrap_err();
+        assert_eq!(
+            "packet 2: number 1.5 is not a non-negative 64-bit integer (at [1][0])",
+            err
+        );
+        for s in [
+            "[[-1]]",
+            "[[1e3]]",
+            "[[\"1\"]]",
+            "[[true]]",
+            "[[null]]",
+            "[[{}]]",
+            "[1]",
+            "{\"packets\": []}",
+            "[[1]",
+            "[[01]]",
+            "[[1],]",
+        ] {
+            assert!(parse_packets_json(s).is_err(), "{}", s);
+        }
+        let value = r#" {"a": [1, -2.5e+3, true, null], "b\n": "\u00e9\ud83d\ude00\"" } "#
+            .parse::<JsonValue>()
+            .unwrap();
+        assert_eq!(
+            "{\"a\":[1,-2.5e+3,true,null],\"b\\n\":\"\u{e9}\u{1f600}\\\"\"}",
+            value.to_string()
+        );
+        assert_eq!(
+            value,
+            value.to_pretty_string().parse::<JsonValue>().unwrap()
+        );
+    }
+
+    /// Tests the comparison explanations for the example pairs against the problem statement.
+    #[test]
+    fn test_day13_explain_comparison_t001() {
+        let input = process_input_file("./input/test/day13_t001.txt");
+        assert_eq!(
+            concat!(
+                "- Compare [[1],[2,3,4]] vs [[1],4]\n",
+                "  - Compare [1] vs [1]\n",
+                "    - Compare 1 vs 1\n",
+                "  - Compare [2,3,4] vs 4\n",
+                "    - Mixed types; convert right to [4] and retry comparison\n",
+                "    - Compare [2,3,4] vs [4]\n",
+                "      - Compare 2 vs 4\n",
+                "        - Left side is smaller, so inputs are in the right order"
+            ),
+            get_pair_explanation(&input, 2).unwrap()
+        );
+        assert_eq!(
+            concat!(
+                "- Compare [9] vs [[8,7,6]]\n",
+                "  - Compare 9 vs [8,7,6]\n",
+                "    - Mixed types; convert left to [9] and retry comparison\n",
+                "    - Compare [9] vs [8,7,6]\n",
+                "      - Compare 9 vs 8\n",
+                "        - Right side is smaller, so inputs are not in the right order"
+            ),
+            get_pair_explanation(&input, 3).unwrap()
+        );
+        assert_eq!(
+            concat!(
+                "- Compare [[[]]] vs [[]]\n",
+                "  - Compare [[]] vs []\n",
+                "    - Right side ran out of items, so inputs are not in the right order"
+            ),
+            get_pair_explanation(&input, 7).unwrap()
+        );
+        for (i, (left, right)) in input.iter().enumerate() {
+            let explanation = get_pair_explanation(&input, i + 1).unwrap();
+            assert_eq!(
+                left < right,
+                explanation.ends_with("so inputs are in the right order")
+            );
+        }
+        assert!(get_pair_explanation(&input, 0).is_err());
+        assert!(get_pair_explanation(&input, 9).is_err());
+        let same = "[1]".parse::<Packet>().unwrap();
+        assert!(explain_comparison(&same, &same).ends_with("cannot be decided"));
+    }
+
+    /// Tests the parsing of the runner arguments used to select pairs to explain.
+    #[test]
+    fn test_day13_explain_args() {
+        let args = |s: &str| {
+            s.split_whitespace()
+                .map(String::from)
+                .collect::<Vec<String>>()
+        };
+        assert_eq!(Ok(vec![]), parse_explain_args(&args("")));
+        assert_eq!(
+            Ok(vec![2, 5]),
+            parse_explain_args(&args("--explain 2 --explain 5"))
+        );
+        assert!(parse_explain_args(&args("--explain")).is_err());
+        assert!(parse_explain_args(&args("--explain x")).is_err());
+        assert!(parse_explain_args(&args("2")).is_err());
+    }
 }
@@ -1,19 +1,14 @@
-use std::collections::HashMap;
 use std::fs;
 use std::time::Instant;
 
 use aoc2022::utils::cartography::Point2D;
+use aoc2022::utils::cave::{parse_rock_paths, PourReport, SandCave};
 
 const PROBLEM_NAME: &str = "Regolith Reservoir";
 const PROBLEM_INPUT_FILE: &str = "./input/day14.txt";
 const PROBLEM_DAY: u64 = 14;
-
-/// Represents a single tile type in the cave map.
-#[derive(Copy, Clone, PartialEq, Eq)]
-enum TileType {
-    Rock,
-    Sand,
-}
+const SAND_SOURCE: Point2D = Point2D::new(500, 0);
+const FLOOR_OFFSET: i64 = 2;
 
 /// Processes the AOC 2022 Day 14 input file and solves both parts of the problem. Solutions are
 /// printed to stdout.
@@ -49,120 +44,48 @@ pub fn main() {
 }
 
 /// Processes the AOC 2022 Day 14 input file in the format required by the solver functions.
-/// Returned value is hashmap representing the locations of cave rock specified in the input file.
-fn process_input_file(filename: &str) -> HashMap<Point2D, TileType> {
+/// Returned value is vector of the rock paths specified in the input file.
+fn process_input_file(filename: &str) -> Vec<Vec<Point2D>> {
     // Read contents of problem input file
     let raw_input = fs::read_to_string(filename).unwrap();
     // Process input file contents into data structure
-    let mut cave_map: HashMap<Point2D, TileType> = HashMap::new();
-    for line in raw_input.lines() {
-        // Trim line and skip if empty
-        let line = line.trim();
-        if line.is_empty() {
-            continue;
-        }
-        // Split the line into points in the rock segments
-        let mut points: Vec<(i64, i64)> = vec![];
-        for point_raw in line.split(" -> ") {
-            let pair = point_raw
-                .split(',')
-                .map(|x| x.parse::<i64>().unwrap())
-                .collect::<Vec<i64>>();
-            points.push((pair[0], pair[1]));
-        }
-        // Draw the rock segments
-        for i in 1..points.len() {
-            // Determine the end points of the rock segments
-            let x_vals = vec![points[i - 1].0, points[i].0];
-            let y_vals = vec![points[i - 1].1, points[i].1];
-            let x_from = *x_vals.iter().min().unwrap();
-            let y_from = *y_vals.iter().min().unwrap();
-            let x_to = *x_vals.iter().max().unwrap();
-            let y_to = *y_vals.iter().max().unwrap();
-            // Check if the rock segment is horizontal or vertical
-            if x_from == x_to {
-                for y in y_from..=y_to {
-                    cave_map.insert(Point2D::new(x_from, y), TileType::Rock);
-                }
-            } else if y_from == y_to {
-                for x in x_from..=x_to {
-                    cave_map.insert(Point2D::new(x, y_from), TileType::Rock);
-                }
-            } else {
-                panic!("Day 14 - bad element in cave map line!");
-            }
-        }
+    match parse_rock_paths(&raw_input) {
+        Ok(paths) => paths,
+        Err(e) => panic!("Day 14 - {}", e),
     }
-    cave_map
 }
 
 /// Solves AOC 2022 Day 14 Part 1 // Determines the number of units of sand that come to rest before
 /// sand falls into the abyss.
-fn solve_part1(input: &HashMap<Point2D, TileType>) -> usize {
-    simulate_cave_sand_falling(input, false)
+fn solve_part1(rock_paths: &[Vec<Point2D>]) -> usize {
+    simulate_cave_sand_falling(rock_paths, &[SAND_SOURCE], None).grains_at_rest()
 }
 
 /// Solves AOC 2022 Day 14 Part 2 // Determines the number of units of sand that come to rest when
 /// the cave floor is included.
-fn solve_part2(input: &HashMap<Point2D, TileType>) -> usize {
-    simulate_cave_sand_falling(input, true)
+fn solve_part2(rock_paths: &[Vec<Point2D>]) -> usize {
+    simulate_cave_sand_falling(rock_paths, &[SAND_SOURCE], Some(FLOOR_OFFSET)).grains_at_rest()
 }
 
-/// Simulates the sand falling into the cave, starting at (x,y):(500,0). Returns the number of units
-/// of sand that come to rest.
-fn simulate_cave_sand_falling(input: &HashMap<Point2D, TileType>, include_floor: bool) -> usize {
-    let mut cave_map = input.clone();
-    let max_y = cave_map.keys().map(|loc| loc.y()).max().unwrap();
-    let sand_origin = Point2D::new(500, 0);
-    loop {
-        let mut sand_loc = sand_origin;
-        let mut reached_base_case = false;
-        loop {
-            // Check if the sand is in the abyss
-            if !include_floor && sand_loc.y() > max_y {
-                reached_base_case = true;
-                break;
-            }
-            if include_floor && sand_loc.y() == max_y + 1 {
-                cave_map.insert(sand_loc, TileType::Sand);
-                break;
-            }
-            // Check where the sand moves to
-            if !cave_map.contains_key(&sand_loc.peek_move_point(0, 1)) {
-                // Try to move directly down
-                sand_loc.move_point(0, 1);
-                continue;
-            } else if !cave_map.contains_key(&sand_loc.peek_move_point(-1, 1)) {
-                // Try to move down diag left
-                sand_loc.move_point(-1, 1);
-                continue;
-            } else if !cave_map.contains_key(&sand_loc.peek_move_point(1, 1)) {
-                // Try to move down diag right
-                sand_loc.move_point(1, 1);
-                continue;
-            } else {
-                // Sand comes to rest
-                cave_map.insert(sand_loc, TileType::Sand);
-                if include_floor && sand_loc == sand_origin {
-                    reached_base_case = true;
-                }
-                break;
-            }
-        }
-        // Check if base case has been reached - return the number of sand units at rest
-        if reached_base_case {
-            return cave_map
-                .values()
-                .copied()
-                .filter(|tile| *tile == TileType::Sand)
-                .count();
-        }
+/// Simulates the sand falling into the cave from the sources until sand falls into the abyss or
+/// all of the sources are blocked.
+fn simulate_cave_sand_falling(
+    rock_paths: &[Vec<Point2D>],
+    sources: &[Point2D],
+    floor_offset: Option<i64>,
+) -> PourReport {
+    match SandCave::new(rock_paths, sources, floor_offset) {
+        Ok(mut cave) => cave.pour(None),
+        Err(e) => panic!("Day 14 - {}", e),
     }
 }
 
 #[cfg(test)]
 mod test {
     use super::*;
+    use std::collections::HashSet;
+
+    use aoc2022::utils::cave::{CaveTile, GrainOutcome, PourEnd};
 
     /// Tests the Day 14 Part 1 solver method against the actual problem solution.
     #[test]
@@ -179,4 +102,95 @@ mod test {
         let solution = solve_part2(&input);
         assert_eq!(23390, solution);
     }
+
+    /// Tests the Day 14 Part 1 solver method against the example input.
+    #[test]
+    fn test_day14_part1_t001() {
+        let input = process_input_file("./input/test/day14_t001.txt");
+        let solution = solve_part1(&input);
+        assert_eq!(24, solution);
+    }
+
+    /// Tests the Day 14 Part 2 solver method against the example input.
+    #[test]
+    fn test_day14_part2_t001() {
+        let input = process_input_file("./input/test/day14_t001.txt");
+        let solution = solve_part2(&input);
+        assert_eq!(93, solution);
+    }
+
+    /// Tests the reports of how pouring sand into the example cave ended.
+    #[test]
+    fn test_day14_pour_report_t001() {
+        let input = process_input_file("./input/test/day14_t001.txt");
+        let report = simulate_cave_sand_falling(&input, &[SAND_SOURCE], None);
+        assert_eq!(
+            PourEnd::Abyss {
+                source: SAND_SOURCE
+            },
+            report.end()
+        );
+        assert!(report.blocked_sources().is_empty());
+        let report = simulate_cave_sand_falling(&input, &[SAND_SOURCE], Some(FLOOR_OFFSET));
+        assert_eq!(PourEnd::SourcesBlocked, report.end());
+        assert_eq!(&[(SAND_SOURCE, 93)], report.blocked_sources());
+        let mut cave = SandCave::new(&input, &[SAND_SOURCE], None).unwrap();
+        assert_eq!(
+            GrainOutcome::Rest {
+                loc: Point2D::new(500, 8)
+            },
+            cave.drop_grain(0)
+        );
+        assert_eq!(PourEnd::GrainLimit, cave.pour(Some(5)).end());
+        assert_eq!(5, cave.grains_at_rest());
+        assert_eq!(Some(CaveTile::Sand), cave.tile(&Point2D::new(499, 8)));
+        assert_eq!(Some(CaveTile::Rock), cave.tile(&Point2D::new(502, 9)));
+    }
+
+    /// Tests pouring sand from multiple sources and with different floor depths.
+    #[test]
+    fn test_day14_multiple_sources_and_floors_t001() {
+        let input = process_input_file("./input/test/day14_t001.txt");
+        // A second source directly below the first is blocked first, after which the first
+        // source continues to fill the same pile
+        let sources = [SAND_SOURCE, Point2D::new(500, 5)];
+        let report = simulate_cave_sand_falling(&input, &sources, Some(FLOOR_OFFSET));
+        assert_eq!(PourEnd::SourcesBlocked, report.end());
+        assert_eq!(93, report.grains_at_rest());
+        assert_eq!(sources[1], report.blocked_sources()[0].0);
+        assert_eq!((SAND_SOURCE, 93), report.blocked_sources()[1]);
+        // A floor directly below the lowest rock fills a pile bounded by the rock shelf
+        let shallow = simulate_cave_sand_falling(&input, &[SAND_SOURCE], Some(1));
+        let deep = simulate_cave_sand_falling(&input, &[SAND_SOURCE], Some(5));
+        assert!(shallow.grains_at_rest() < 93 && 93 < deep.grains_at_rest());
+        // The fall path speed-up must give the same results as dropping every grain in isolation
+        let sources = [Point2D::new(495, 0), Point2D::new(505, 0)];
+        let report = simulate_cave_sand_falling(&input, &sources, Some(FLOOR_OFFSET));
+        let mut cave = SandCave::new(&input, &sources, Some(FLOOR_OFFSET)).unwrap();
+        let mut grains = 0;
+        while cave.drop_grain(0) != GrainOutcome::Blocked {
+            grains += 1;
+        }
+        while cave.drop_grain(1) != GrainOutcome::Blocked {
+            grains += 1;
+        }
+        assert_eq!(grains, report.grains_at_rest());
+        // With a floor, every empty tile reachable by a falling grain ends up filled
+        let mut reachable: Vec<Point2D> = sources.to_vec();
+        let mut seen: HashSet<Point2D> = HashSet::from(sources);
+        while let Some(loc) = reachable.pop() {
+            for dx in [-1, 0, 1] {
+                let next = loc.peek_move_point(dx, 1);
+                if next.y() < 11 && cave.tile(&next) != Some(CaveTile::Rock) && seen.insert(next) {
+                    reachable.push(next);
+                }
+            }
+        }
+        assert_eq!(seen.len(), grains);
+        assert_eq!(2, report.blocked_sources().len());
+        assert!(SandCave::new(&input, &[], None).is_err());
+        assert!(SandCave::new(&input, &[Point2D::new(498, 5)], None).is_err());
+        assert!(parse_rock_paths("498,4 -> 498").is_err());
+        assert!(SandCave::new(&parse_rock_paths("1,1 -> 2,2").unwrap(), &sources, None).is_err());
+    }
 }
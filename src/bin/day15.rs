@@ -1,17 +1,18 @@
-use std::collections::HashSet;
 use std::fs;
-use std::ops::RangeInclusive;
 use std::time::Instant;
 
 use regex::Regex;
 
 use aoc2022::utils::cartography::Point2D;
+use aoc2022::utils::sensors::SensorField;
 
 const PROBLEM_NAME: &str = "Beacon Exclusion Zone";
 const PROBLEM_INPUT_FILE: &str = "./input/day15.txt";
 const PROBLEM_DAY: u64 = 15;
 const PART1_TARGET_ROW: i64 = 2000000;
-const PART2_ROW_LIMIT: i64 = 4000000;
+const PART2_SEARCH_MIN: i64 = 0;
+const PART2_SEARCH_MAX: i64 = 4000000;
+const TUNING_MULTIPLIER: i64 = 4000000;
 
 /// Processes the AOC 2022 Day 15 input file and solves both parts of the problem. Solutions are
 /// printed to stdout.
@@ -47,9 +48,8 @@ pub fn main() {
 }
 
 /// Processes the AOC 2022 Day 15 input file in the format required by the solver functions.
-/// Returned value is vector of tuples containing the sensor locations and the location of their
-/// closest beacon.
-fn process_input_file(filename: &str) -> Vec<(Point2D, Point2D)> {
+/// Returned value is the field of sensors specified in the input file.
+fn process_input_file(filename: &str) -> SensorField {
     // Read contents of problem input file
     let raw_input = fs::read_to_string(filename).unwrap();
     // Process input file contents into data structure
@@ -74,86 +74,43 @@ fn process_input_file(filename: &str) -> Vec<(Point2D, Point2D)> {
         let loc_beac = Point2D::new(x_beac, y_beac);
         output.push((loc_sens, loc_beac))
     }
-    output
+    SensorField::new(&output)
 }
 
 /// Solves AOC 2022 Day 15 Part 1 // Determines the number of locations in the row where y=2000000
 /// which cannot contain a beacon.
-fn solve_part1(input: &[(Point2D, Point2D)]) -> usize {
-    let mut target_row_locs: HashSet<Point2D> = HashSet::new();
-    let beacons_in_target_row = input
+fn solve_part1(field: &SensorField) -> u64 {
+    // Beacons are always covered by their sensor, so exclude those in the target row
+    let beacons_in_target_row = field
+        .beacons()
         .iter()
-        .map(|x| x.1)
-        .filter(|x| x.y() == PART1_TARGET_ROW)
-        .collect::<HashSet<Point2D>>();
-    for (loc_sens, loc_beac) in input {
-        let output = find_beacon_exclusion_locations_in_row(loc_sens, loc_beac, PART1_TARGET_ROW);
-        target_row_locs.extend(output);
-    }
-    for beacon in beacons_in_target_row {
-        if target_row_locs.contains(&beacon) {
-            target_row_locs.remove(&beacon);
-        }
-    }
-    target_row_locs.len()
+        .filter(|beacon| beacon.y() == PART1_TARGET_ROW)
+        .count() as u64;
+    field.covered_length(PART1_TARGET_ROW) - beacons_in_target_row
 }
 
 /// Solves AOC 2022 Day 15 Part 2 // Determines the tuning frequency of the distress beacon.
-fn solve_part2(input: &[(Point2D, Point2D)]) -> i64 {
-    for y in 0..=PART2_ROW_LIMIT {
-        let mut ranges: Vec<RangeInclusive<i64>> = vec![];
-        // Find the exclusion zones in the current row from the sensors
-        for (loc_sens, loc_beac) in input {
-            let mdist = loc_sens.calculate_manhattan_distance(loc_beac) as i64;
-            let delta_y = (loc_sens.y() - y).abs();
-            if delta_y > mdist {
-                continue;
-            }
-            let min_x = loc_sens.x() - mdist + delta_y;
-            let max_x = loc_sens.x() + mdist - delta_y;
-            // Skip if the x value is outside the search area
-            if min_x > PART2_ROW_LIMIT || max_x < 0 {
-                continue;
-            }
-            ranges.push(min_x..=max_x);
-        }
-        // Sort the ranges based on their start value
-        ranges.sort_by(|a, b| a.start().cmp(b.start()));
-        // Compare the ranges to find the gap where the distress beacon is located
-        let mut left = 0;
-        let mut right = 1;
-        loop {
-            if right >= ranges.len() {
-                break;
-            }
-            // Check if the x location of the distress beacon has been found
-            if ranges[right].start() - ranges[left].end() == 2 {
-                return (ranges[left].end() + 1) * 4000000 + y;
-            }
-            if ranges[right].end() > ranges[left].end() {
-                left = right;
-                right = left + 1;
-            } else {
-                right += 1;
-            }
-        }
-    }
-    panic!("Day 15 Part 2 - should not get here!");
+fn solve_part2(field: &SensorField) -> i64 {
+    let search_min = Point2D::new(PART2_SEARCH_MIN, PART2_SEARCH_MIN);
+    let search_max = Point2D::new(PART2_SEARCH_MAX, PART2_SEARCH_MAX);
+    find_tuning_frequency(field, &search_min, &search_max, TUNING_MULTIPLIER)
 }
 
-/// Finds the locations in the specified row that could not contain a beacon.
-fn find_beacon_exclusion_locations_in_row(
-    loc_sens: &Point2D,
-    loc_beac: &Point2D,
-    target_row: i64,
-) -> HashSet<Point2D> {
-    let m_dist = loc_sens.calculate_manhattan_distance(loc_beac) as i64;
-    let delta_y = (loc_sens.y() - target_row).abs();
-    let mut output: HashSet<Point2D> = HashSet::new();
-    for x in (loc_sens.x() - m_dist + delta_y)..=(loc_sens.x() + m_dist - delta_y) {
-        output.insert(Point2D::new(x, target_row));
+/// Finds the distress beacon, which is the only location within the search box (inclusive) that
+/// is not covered by a sensor. Returned value is the tuning frequency of the distress beacon, given
+/// by its x-coordinate multiplied by the multiplier and then added to its y-coordinate.
+fn find_tuning_frequency(
+    field: &SensorField,
+    search_min: &Point2D,
+    search_max: &Point2D,
+    multiplier: i64,
+) -> i64 {
+    // Only two uncovered locations are needed to tell that the distress beacon is not unique
+    match field.uncovered_points(search_min, search_max, 2)[..] {
+        [beacon] => beacon.x() * multiplier + beacon.y(),
+        [] => panic!("Day 15 Part 2 - did not find the distress beacon!"),
+        _ => panic!("Day 15 Part 2 - found more than one possible distress beacon location!"),
     }
-    output
 }
 
 #[cfg(test)]
@@ -175,4 +132,118 @@ mod test {
         let solution = solve_part2(&input);
         assert_eq!(11840879211051, solution);
     }
+
+    /// Tests the Day 15 Part 2 solution method against the example input, with the search box and
+    /// tuning frequency multiplier given in the problem statement.
+    #[test]
+    fn test_day15_part2_t001() {
+        let input = process_input_file("./input/test/day15_t001.txt");
+        let (search_min, search_max) = (Point2D::new(0, 0), Point2D::new(20, 20));
+        let solution = find_tuning_frequency(&input, &search_min, &search_max, 4000000);
+        assert_eq!(56000011, solution);
+    }
+
+    /// Tests that the uncovered points found in search boxes match those found by checking every
+    /// point in the box, over the example input and fields with uncovered diagonal strips, and that
+    /// the search stops at the limit in a large box with little coverage.
+    #[test]
+    fn test_day15_uncovered_points_t001() {
+        let brute_force = |field: &SensorField, min: &Point2D, max: &Point2D| {
+            let mut expected: Vec<Point2D> = vec![];
+            for x in min.x()..=max.x() {
+                for y in min.y()..=max.y() {
+                    let loc = Point2D::new(x, y);
+                    if !field.is_covered(&loc) {
+                        expected.push(loc);
+                    }
+                }
+            }
+            expected
+        };
+        let point = |(x, y): (i64, i64)| Point2D::new(x, y);
+        let input = process_input_file("./input/test/day15_t001.txt");
+        let boxes = [
+            ((0, 0), (20, 20)),
+            ((14, 11), (14, 11)),
+            ((-2, 20), (2, 24)),
+            ((-5, -5), (30, 30)),
+            ((0, 0), (13, 20)),
+        ];
+        for (min, max) in boxes {
+            let (min, max) = (point(min), point(max));
+            assert_eq!(
+                brute_force(&input, &min, &max),
+                input.uncovered_points(&min, &max, usize::MAX)
+            );
+        }
+        // Strip between two sensors whose boundaries run along the same diagonal line
+        let strip = SensorField::new(&[
+            (point((0, 0)), point((2, 0))),
+            (point((4, 4)), point((8, 4))),
+        ]);
+        let (min, max) = (point((0, 0)), point((3, 3)));
+        let expected = [(0, 3), (1, 2), (2, 1), (3, 0)].map(point).to_vec();
+        assert_eq!(expected, brute_force(&strip, &min, &max));
+        assert_eq!(expected, strip.uncovered_points(&min, &max, usize::MAX));
+        // Strip closed off at both ends by sensors touching it only diagonally
+        let closed = SensorField::new(&[
+            (point((10, 0)), point((10, 9))),
+            (point((0, 10)), point((0, 1))),
+            (point((-1, -1)), point((-1, 1))),
+            (point((11, 11)), point((11, 13))),
+        ]);
+        let (min, max) = (point((0, 0)), point((10, 10)));
+        let expected = (1..=9).map(|k| point((k, k))).collect::<Vec<Point2D>>();
+        assert_eq!(expected, brute_force(&closed, &min, &max));
+        assert_eq!(expected, closed.uncovered_points(&min, &max, usize::MAX));
+        let limited = closed.uncovered_points(&min, &max, 3);
+        assert_eq!(3, limited.len());
+        assert!(limited.iter().all(|loc| expected.contains(loc)));
+        assert!(closed.uncovered_points(&min, &max, 0).is_empty());
+        // Large box with little coverage stops at the limit
+        let sparse = SensorField::new(&[(point((5, 5)), point((6, 6)))]);
+        let (min, max) = (point((0, 0)), point((4_000_000, 4_000_000)));
+        let limited = sparse.uncovered_points(&min, &max, 2);
+        assert_eq!(2, limited.len());
+        assert!(limited.iter().all(|loc| !sparse.is_covered(loc)));
+    }
+
+    /// Tests the coverage queries of the sensor field against the example input.
+    #[test]
+    fn test_day15_coverage_queries_t001() {
+        let input = process_input_file("./input/test/day15_t001.txt");
+        assert_eq!(27, input.covered_length(10));
+        assert_eq!(vec![-2..=24], input.row_intervals(10));
+        assert_eq!(vec![-3..=13, 15..=25], input.row_intervals(11));
+        assert!(input.is_covered(&Point2D::new(2, 10)));
+        assert!(!input.is_covered(&Point2D::new(14, 11)));
+        assert_eq!(vec![6, 7], input.covered_by(&Point2D::new(8, 0)));
+        assert_eq!(vec![6, 7, 8], input.covered_by(&Point2D::new(2, 10)));
+        assert!(input.covered_by(&Point2D::new(14, 11)).is_empty());
+        assert!(input.gapped_rows(0, 20).contains(&11));
+        for y in input.gapped_rows(-10, 30) {
+            let intervals = input.row_intervals(y);
+            let gap = Point2D::new(intervals[0].end() + 1, y);
+            assert!(!input.is_covered(&gap));
+        }
+        assert!(!input.gapped_rows(-10, 30).contains(&10));
+    }
+
+    /// Tests the coverage density rendering against the example input.
+    #[test]
+    fn test_day15_density_rendering_t001() {
+        let input = process_input_file("./input/test/day15_t001.txt");
+        let (min, max) = (Point2D::new(0, 0), Point2D::new(20, 20));
+        let full = input.render_density(&min, &max, 21, 21);
+        assert_eq!(21, full.lines().count());
+        assert_eq!(' ', full.lines().nth(11).unwrap().chars().nth(14).unwrap());
+        assert_eq!(1, full.chars().filter(|c| *c == ' ').count());
+        let coarse = input.render_density(&min, &max, 3, 3);
+        assert_eq!(
+            vec!["@@@", "@@%", "@@@"],
+            coarse.lines().collect::<Vec<&str>>()
+        );
+        let (min, max) = (Point2D::new(-30, 0), Point2D::new(-21, 9));
+        assert_eq!("  \n  \n", input.render_density(&min, &max, 2, 2));
+    }
 }
@@ -1,26 +1,40 @@
-use std::collections::{HashMap, HashSet, VecDeque};
+use std::env;
 use std::fs;
-use std::rc::Rc;
 use std::time::Instant;
 
-use regex::Regex;
+use aoc2022::utils::volcano::{
+    render_compressed_dot, render_tunnel_dot, ValveNetwork, ValveOptimiser, ValvePlan,
+};
 
 const PROBLEM_NAME: &str = "Proboscidea Volcanium";
 const PROBLEM_INPUT_FILE: &str = "./input/day16.txt";
 const PROBLEM_DAY: u64 = 16;
 
+const START_VALVE: &str = "AA";
 const PART1_MINUTES: u64 = 30; // allowed 30 minutes for Part 1
 const PART2_MINUTES: u64 = 26; // allowed 26 minutes for Part 2
+const PART1_AGENTS: usize = 1; // protagonist only for Part 1
+const PART2_AGENTS: usize = 2; // protagonist and elephant for Part 2
 
-/// Type definition to simplify function signatures.
-type ProblemInput = (
-    HashMap<Rc<String>, u64>,
-    HashMap<Rc<String>, Vec<Rc<String>>>,
-);
+/// Formats that the plans giving the solutions can be printed in. The DOT formats give the tunnel
+/// network or its compressed graph, with the plan highlighted.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+enum PlanFormat {
+    Log,
+    Json,
+    TunnelDot,
+    CompressedDot,
+}
 
 /// Processes the AOC 2022 Day 16 input file and solves both parts of the problem. Solutions are
-/// printed to stdout.
+/// printed to stdout. The plans giving the solutions can be printed as a minute-by-minute log or as
+/// JSON with the "--plan" option (e.g. "--plan log --plan json"), or highlighted on a Graphviz DOT
+/// graph of the tunnels with the "--dot" option (e.g. "--dot tunnels --dot compressed").
 pub fn main() {
+    let plan_formats = match parse_plan_args(&env::args().skip(1).collect::<Vec<String>>()) {
+        Ok(formats) => formats,
+        Err(e) => panic!("Day 16 - {}", e),
+    };
     let start = Instant::now();
     // Input processing
     let input = process_input_file(PROBLEM_INPUT_FILE);
@@ -39,6 +53,21 @@ pub fn main() {
     println!("AOC 2022 Day {} - \"{}\"", PROBLEM_DAY, PROBLEM_NAME);
     println!("[+] Part 1: {}", p1_solution);
     println!("[+] Part 2: {}", p2_solution);
+    if !plan_formats.is_empty() {
+        let parts = [
+            (1, PART1_MINUTES, PART1_AGENTS),
+            (2, PART2_MINUTES, PART2_AGENTS),
+        ];
+        for (part, minutes, agents) in parts {
+            // The optimiser and plan for each part are shared by all of the formats
+            let optimiser = get_valve_optimiser(&input, START_VALVE, minutes);
+            let plan = get_valve_plan(&optimiser, agents);
+            for format in &plan_formats {
+                let output = render_plan(&input, &optimiser, &plan, *format);
+                println!("== Part {} plan ==\n{}", part, output);
+            }
+        }
+    }
     println!("~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~");
     println!("Execution times:");
     println!("[+] Input:  {:.2?}", input_parser_duration);
@@ -52,297 +81,106 @@ pub fn main() {
 }
 
 /// Processes the AOC 2022 Day 16 input file in the format required by the solver functions.
-/// Returned value is tuple containing hashmaps with the valve flow rates and valve connections.
-fn process_input_file(filename: &str) -> ProblemInput {
+/// Returned value is the network of valves specified in the input file.
+fn process_input_file(filename: &str) -> ValveNetwork {
     // Read contents of problem input file
     let raw_input = fs::read_to_string(filename).unwrap();
     // Process input file contents into data structure
-    let regex_line = Regex::new(
-        r"^Valve ([A-Z]{2}) has flow rate=(\d+); tunnel[s]? lead[s]? to valve[s]? (.*)$",
-    )
-    .unwrap();
-    let mut valve_flow_rates: HashMap<Rc<String>, u64> = HashMap::new();
-    let mut valve_connections: HashMap<Rc<String>, Vec<Rc<String>>> = HashMap::new();
-    for line in raw_input.lines() {
-        // Trim input line and ignore empty line
-        let line = line.trim();
-        if line.is_empty() {
-            continue;
-        }
-        // Extract field data from input line
-        let caps = regex_line.captures(line).unwrap();
-        let valve = Rc::new(String::from(&caps[1]));
-        let flow_rate = caps[2].parse::<u64>().unwrap();
-        let connections = caps[3]
-            .split(", ")
-            .map(|elem| Rc::new(elem.to_string()))
-            .collect::<Vec<Rc<String>>>();
-        valve_flow_rates.insert(valve.clone(), flow_rate);
-        valve_connections.insert(valve.clone(), connections);
+    match ValveNetwork::parse(&raw_input) {
+        Ok(network) => network,
+        Err(e) => panic!("Day 16 - {}", e),
     }
-    (valve_flow_rates, valve_connections)
 }
 
 /// Solves AOC 2022 Day 16 Part 1 // Gets the maximum pressure that can be released by opening
 /// valves in the volcano over 30 minutes.
-fn solve_part1(input: &ProblemInput) -> u64 {
-    // Calculate the valve activation times
-    let (valve_flow_rates, valve_connections) = input;
-    let valve_activation_times = &get_valve_activation_times(valve_flow_rates, valve_connections);
-    let possible_paths = determine_possible_paths("AA", valve_activation_times, PART1_MINUTES);
-    let mut max_pressure_released = 0;
-    for path in possible_paths.iter() {
-        let pressure_released = get_pressure_released_for_path(
-            path,
-            valve_flow_rates,
-            valve_activation_times,
-            PART1_MINUTES,
-        );
-        if pressure_released > max_pressure_released {
-            max_pressure_released = pressure_released;
-        }
-    }
-    max_pressure_released
+fn solve_part1(network: &ValveNetwork) -> u64 {
+    get_max_pressure_released(network, START_VALVE, PART1_MINUTES, PART1_AGENTS)
 }
 
 /// Solves AOC 2022 Day 16 Part 2 // Gets the maximum pressures that can be released by opening
 /// valves alongside the elephant over 26 minutes.
-fn solve_part2(input: &ProblemInput) -> u64 {
-    let (valve_flow_rates, valve_connections) = input;
-    let valve_activation_times = &get_valve_activation_times(valve_flow_rates, valve_connections);
-    // Find the protagonist paths
-    let possible_paths = determine_possible_paths("AA", valve_activation_times, PART2_MINUTES);
-    let mut maximum_pressure_released = 0;
-    for protagonist_path in possible_paths.iter() {
-        // Find the paths the elephant could take for a given protagonist path
-        let elephant_paths = get_elephant_paths(
-            "AA",
-            protagonist_path,
-            valve_activation_times,
-            PART2_MINUTES,
-        );
-        for ele_path in elephant_paths.iter() {
-            let mut pressure_released = 0;
-            // Calculate the pressure released over the allowed time by the protagonist and elephant
-            pressure_released += get_pressure_released_for_path(
-                protagonist_path,
-                valve_flow_rates,
-                valve_activation_times,
-                PART2_MINUTES,
-            );
-            pressure_released += get_pressure_released_for_path(
-                ele_path,
-                valve_flow_rates,
-                valve_activation_times,
-                PART2_MINUTES,
-            );
-            // Check if a new maximum pressure released value has been found
-            if pressure_released > maximum_pressure_released {
-                maximum_pressure_released = pressure_released;
-            }
-        }
-    }
-    maximum_pressure_released
+fn solve_part2(network: &ValveNetwork) -> u64 {
+    get_max_pressure_released(network, START_VALVE, PART2_MINUTES, PART2_AGENTS)
 }
 
-/// Determines the amount of pressure released over the allowed time by following the given path.
-/// The time required to move to and activate a valve is provided as parameter to this function.
-fn get_pressure_released_for_path(
-    path: &Vec<Rc<String>>,
-    valve_flow_rates: &HashMap<Rc<String>, u64>,
-    valve_activation_times: &HashMap<Rc<String>, HashMap<Rc<String>, u64>>,
-    minutes_allowed: u64,
+/// Gets the maximum pressure that can be released by the given number of agents working together,
+/// all starting at the start valve with the given number of minutes available.
+fn get_max_pressure_released(
+    network: &ValveNetwork,
+    start_valve: &str,
+    minutes: u64,
+    agents: usize,
 ) -> u64 {
-    let mut minutes_remaining = minutes_allowed;
-    let mut pressure_per_minute = 0;
-    let mut total_pressure_released = 0;
-    // Start from the second element, since the first element is not moved TO
-    for i in 1..path.len() {
-        // Get activation time
-        let activation_time = valve_activation_times
-            .get(&path[i - 1])
-            .unwrap()
-            .get(&path[i])
-            .unwrap();
-        // Sum up pressure released while travelling to and activating valve
-        total_pressure_released += pressure_per_minute * activation_time;
-        // Add new valve's flow rate to the pressure released per minute
-        pressure_per_minute += valve_flow_rates.get(&path[i]).unwrap();
-        // Reduce the time remaining by the activation time
-        minutes_remaining -= activation_time;
+    match get_valve_optimiser(network, start_valve, minutes).max_pressure(agents) {
+        Ok(pressure) => pressure,
+        Err(e) => panic!("Day 16 - {}", e),
     }
-    // Use up the remaining time to release pressure
-    total_pressure_released += pressure_per_minute * minutes_remaining;
-    // Result the resulting pressure released
-    total_pressure_released
 }
 
-/// Gets the time required to move from a valve with a non-zero flow rate (or the start valve "AA")
-/// to another valve with flow.
-fn get_valve_activation_times(
-    valve_flow_rates: &HashMap<Rc<String>, u64>,
-    valve_connections: &HashMap<Rc<String>, Vec<Rc<String>>>,
-) -> HashMap<Rc<String>, HashMap<Rc<String>, u64>> {
-    let mut output: HashMap<Rc<String>, HashMap<Rc<String>, u64>> = HashMap::new();
-    // Determine the valves to include in the activation time map
-    let mut valid_valves: HashSet<Rc<String>> = HashSet::new();
-    valid_valves.insert(Rc::new(String::from("AA")));
-    for (valve, flow_rate) in valve_flow_rates.iter() {
-        if *flow_rate == 0 {
-            continue;
-        }
-        valid_valves.insert(valve.clone());
+/// Creates the optimiser for agents starting at the start valve with the given number of minutes
+/// available.
+fn get_valve_optimiser(network: &ValveNetwork, start_valve: &str, minutes: u64) -> ValveOptimiser {
+    match ValveOptimiser::new(network, start_valve, minutes) {
+        Ok(optimiser) => optimiser,
+        Err(e) => panic!("Day 16 - {}", e),
     }
-    // Find the activation times for other valid valves for each valid valve
-    for valve in valid_valves.iter() {
-        let valve_activation_times =
-            get_activation_times_from_start_valve(valve, valve_connections, &valid_valves);
-        output.insert(valve.clone(), valve_activation_times);
-    }
-    output
 }
 
-/// Gets the times required to move to and activate the valid valves from the given start valve.
-fn get_activation_times_from_start_valve(
-    start_valve: &Rc<String>,
-    valve_connections: &HashMap<Rc<String>, Vec<Rc<String>>>,
-    valid_valves: &HashSet<Rc<String>>,
-) -> HashMap<Rc<String>, u64> {
-    let mut visit_queue: VecDeque<(u64, Rc<String>)> = VecDeque::new();
-    let mut visited: HashSet<Rc<String>> = HashSet::new();
-    let mut output: HashMap<Rc<String>, u64> = HashMap::new();
-    visit_queue.push_back((0, start_valve.clone()));
-    visited.insert(start_valve.clone());
-    while !visit_queue.is_empty() {
-        // Get next valve to visit
-        let (steps, valve) = visit_queue.pop_front().unwrap();
-        if valid_valves.contains(&valve) {
-            output.insert(valve.clone(), steps + 1);
-        }
-        // Get next nodes to visit
-        for next_valve in valve_connections.get(&valve).unwrap() {
-            if !visited.contains(next_valve) {
-                visited.insert(next_valve.clone());
-                visit_queue.push_back((steps + 1, next_valve.clone()));
-            }
-        }
+/// Finds the plan releasing the maximum pressure for the given number of agents working together.
+fn get_valve_plan(optimiser: &ValveOptimiser, agents: usize) -> ValvePlan {
+    match optimiser.plan(agents) {
+        Ok(plan) => plan,
+        Err(e) => panic!("Day 16 - {}", e),
     }
-    output
 }
 
-/// Determines the paths that are possible in the allowed time when starting from the given start
-/// valve.
-fn determine_possible_paths(
-    start_valve: &str,
-    valve_activation_times: &HashMap<Rc<String>, HashMap<Rc<String>, u64>>,
-    minutes_allowed: u64,
-) -> Vec<Vec<Rc<String>>> {
-    let mut possible_paths: Vec<Vec<Rc<String>>> = vec![];
-    determine_possible_paths_recursive(
-        vec![Rc::new(String::from(start_valve))],
-        minutes_allowed,
-        &mut possible_paths,
-        valve_activation_times,
-    );
-    possible_paths
-}
-
-/// Recursive helper function to find possible paths by building from the current path.
-fn determine_possible_paths_recursive(
-    current_path: Vec<Rc<String>>,
-    time_remaining: u64,
-    possible_paths: &mut Vec<Vec<Rc<String>>>,
-    valve_activation_times: &HashMap<Rc<String>, HashMap<Rc<String>, u64>>,
-) {
-    let current_valve = current_path.last().unwrap();
-    for next_valve in valve_activation_times.keys() {
-        // Look up the activation time
-        let activation_time = *valve_activation_times
-            .get(current_valve)
-            .unwrap()
-            .get(next_valve)
-            .unwrap();
-        // Check if the next valve represents a valid move
-        if current_path.contains(next_valve) || activation_time >= time_remaining {
-            continue;
-        }
-        // Form the new path
-        let mut new_path = current_path.clone();
-        new_path.push(next_valve.clone());
-        // Keep finding new paths
-        determine_possible_paths_recursive(
-            new_path,
-            time_remaining - activation_time,
-            possible_paths,
-            valve_activation_times,
-        );
+/// Renders the plan found by the optimiser in the given format.
+fn render_plan(
+    network: &ValveNetwork,
+    optimiser: &ValveOptimiser,
+    plan: &ValvePlan,
+    format: PlanFormat,
+) -> String {
+    match format {
+        PlanFormat::Log => plan.render_log(),
+        PlanFormat::Json => plan.to_json().to_pretty_string(),
+        PlanFormat::TunnelDot => render_tunnel_dot(network, Some(plan)),
+        PlanFormat::CompressedDot => render_compressed_dot(network, optimiser.graph(), Some(plan)),
     }
-    possible_paths.push(current_path);
 }
 
-/// Determines the possible paths the elephant could take in the time allowed for a given path taken
-/// by the protagonist.
-fn get_elephant_paths(
-    start_valve: &str,
-    protagonist_path: &Vec<Rc<String>>,
-    valve_activation_times: &HashMap<Rc<String>, HashMap<Rc<String>, u64>>,
-    minutes_allowed: u64,
-) -> Vec<Vec<Rc<String>>> {
-    let mut elephant_paths: Vec<Vec<Rc<String>>> = vec![];
-    get_elephant_paths_recursive(
-        protagonist_path,
-        vec![Rc::new(String::from(start_valve))],
-        minutes_allowed,
-        &mut elephant_paths,
-        valve_activation_times,
-    );
-    elephant_paths
-}
-
-/// Recursive helper function to find the possible elephant paths for a given protagonist path.
-fn get_elephant_paths_recursive(
-    protagonist_path: &Vec<Rc<String>>,
-    current_path: Vec<Rc<String>>,
-    time_remaining: u64,
-    possible_paths: &mut Vec<Vec<Rc<String>>>,
-    valve_activation_times: &HashMap<Rc<String>, HashMap<Rc<String>, u64>>,
-) {
-    // Get reference to the current valve - the last valve of the current path
-    let current_valve = current_path.last().unwrap();
-    for next_valve in valve_activation_times.keys() {
-        // Get the activation time for the next valve from the current valve
-        let activation_time = *valve_activation_times
-            .get(current_valve)
-            .unwrap()
-            .get(next_valve)
-            .unwrap();
-        // Check if the next valve is a valid move
-        if current_path.contains(next_valve)
-            || protagonist_path.contains(next_valve)
-            || activation_time >= time_remaining
-        {
-            continue;
-        }
-        // Form the new path
-        let mut new_path = current_path.clone();
-        new_path.push(next_valve.clone());
-        // Keep building elephant paths
-        get_elephant_paths_recursive(
-            protagonist_path,
-            new_path,
-            time_remaining - activation_time,
-            possible_paths,
-            valve_activation_times,
-        );
+/// Parses the command line arguments, which give the formats to print the plans in with the
+/// "--plan" option (either "log" or "json") and the "--dot" option (either "tunnels" or
+/// "compressed").
+fn parse_plan_args(args: &[String]) -> Result<Vec<PlanFormat>, String> {
+    let mut formats: Vec<PlanFormat> = vec![];
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        let format = match (arg.as_str(), args.next().map(|format| format.as_str())) {
+            ("--plan", Some("log")) => PlanFormat::Log,
+            ("--plan", Some("json")) => PlanFormat::Json,
+            ("--plan", _) => return Err(String::from("--plan requires a format (log or json)")),
+            ("--dot", Some("tunnels")) => PlanFormat::TunnelDot,
+            ("--dot", Some("compressed")) => PlanFormat::CompressedDot,
+            ("--dot", _) => {
+                return Err(String::from(
+                    "--dot requires a graph (tunnels or compressed)",
+                ))
+            }
+            _ => return Err(format!("unrecognised argument \"{}\"", arg)),
+        };
+        formats.push(format);
     }
-    // No more possible moves from the current path so add the current path to the possible paths
-    possible_paths.push(current_path);
+    Ok(formats)
 }
 
 #[cfg(test)]
 mod test {
     use super::*;
 
+    use aoc2022::utils::json::JsonValue;
+
     /// Tests the Day 16 Part 1 solver method against the actual problem solution.
     #[test]
     fn test_day16_part1_actual() {
@@ -358,4 +196,263 @@ mod test {
         let solution = solve_part2(&input);
         assert_eq!(2528, solution);
     }
+
+    /// Tests the Day 16 Part 1 solver method against the example input.
+    #[test]
+    fn test_day16_part1_t001() {
+        let input = process_input_file("./input/test/day16_t001.txt");
+        let solution = solve_part1(&input);
+        assert_eq!(1651, solution);
+    }
+
+    /// Tests the Day 16 Part 2 solver method against the example input.
+    #[test]
+    fn test_day16_part2_t001() {
+        let input = process_input_file("./input/test/day16_t001.txt");
+        let solution = solve_part2(&input);
+        assert_eq!(1707, solution);
+    }
+
+    /// Tests the optimiser with other start valves, time limits and numbers of agents.
+    #[test]
+    fn test_day16_optimiser_parameters_t001() {
+        let input = process_input_file("./input/test/day16_t001.txt");
+        // Opening JJ first, then DD and BB
+        assert_eq!(315, get_max_pressure_released(&input, "JJ", 10, 1));
+        assert_eq!(0, get_max_pressure_released(&input, "AA", 1, 1));
+        assert_eq!(0, get_max_pressure_released(&input, "AA", 26, 0));
+        // Every valve is opened by the time the agents run out, so more agents can only help
+        let mut previous = 0;
+        for agents in 1..=4 {
+            let pressure = get_max_pressure_released(&input, "AA", 26, agents);
+            assert!(pressure >= previous);
+            previous = pressure;
+        }
+        assert!(ValveOptimiser::new(&input, "ZZ", 30).is_err());
+        assert!(ValveNetwork::parse("Valve AA has flow rate=0; tunnel leads to valve BB").is_err());
+        let network = ValveNetwork::parse("Valve AA has flow rate=5; tunnel leads to valve AA");
+        assert_eq!(15, get_max_pressure_released(&network.unwrap(), "AA", 4, 1));
+    }
+
+    /// Tests that more than two agents are rejected when there are too many valves worth opening to
+    /// split between them, unless the extra agents have no valves left to open.
+    #[test]
+    fn test_day16_multi_agent_valve_limit() {
+        // Start valve AA leads to a chain of 17 valves worth opening
+        let mut scan = String::from("Valve AA has flow rate=0; tunnel leads to valve V00\n");
+        for i in 0..17 {
+            let tunnels = if i < 16 {
+                format!("valves V{:02}, V{:02}", i.max(1) - 1, i + 1)
+            } else {
+                format!("valve V{:02}", i - 1)
+            };
+            scan += &format!(
+                "Valve V{:02} has flow rate=1; tunnels lead to {}\n",
+                i, tunnels
+            );
+        }
+        let network = ValveNetwork::parse(&scan).unwrap();
+        let optimiser = get_valve_optimiser(&network, "AA", 5);
+        assert_eq!(Ok(5), optimiser.max_pressure(2));
+        assert!(optimiser
+            .max_pressure(3)
+            .unwrap_err()
+            .contains("at most 16"));
+        assert!(optimiser.plan(3).is_err());
+        // With a single valve worth opening, agents after the first have nothing to open
+        let network = ValveNetwork::parse("Valve AA has flow rate=5; tunnel leads to valve AA");
+        let optimiser = get_valve_optimiser(&network.unwrap(), "AA", 4);
+        assert_eq!(Ok(15), optimiser.max_pressure(100));
+        assert_eq!(100, get_valve_plan(&optimiser, 100).agents().len());
+    }
+
+    /// Tests that networks with one-way tunnels are handled, with valves that cannot be reached
+    /// from each other skipped.
+    #[test]
+    fn test_day16_one_way_tunnels() {
+        let scan = concat!(
+            "Valve AA has flow rate=0; tunnels lead to valves BB, CC\n",
+            "Valve BB has flow rate=10; tunnel leads to valve AA\n",
+            "Valve CC has flow rate=5; tunnel leads to valve CC\n",
+            "Valve DD has flow rate=7; tunnel leads to valve AA\n"
+        );
+        let network = ValveNetwork::parse(scan).unwrap();
+        let graph = network.compress("AA").unwrap();
+        assert_eq!(2, graph.valves().len());
+        assert_eq!((Some(2), None), (graph.travel(0, 1), graph.travel(1, 0)));
+        // Opening BB then CC beats opening CC first, after which no other valve can be reached
+        assert_eq!(105, get_max_pressure_released(&network, "AA", 10, 1));
+        assert_eq!(120, get_max_pressure_released(&network, "AA", 10, 2));
+        let optimiser = get_valve_optimiser(&network, "AA", 10);
+        let plan = get_valve_plan(&optimiser, 1);
+        let stops = plan.agents()[0].stops();
+        assert_eq!(vec!["AA", "CC"], stops[1].moves());
+        assert_eq!(105, plan.total_pressure());
+        let dot = render_compressed_dot(&network, &graph, Some(&plan));
+        assert!(
+            dot.contains("  \"BB\" -- \"CC\" [label=\"3\", dir=forward, color=red, penwidth=2];\n")
+        );
+        assert!(!dot.contains("\"CC\" -- \"BB\""));
+        let dot = render_tunnel_dot(&network, None);
+        assert!(dot.contains("  \"AA\" -- \"BB\";\n"));
+        assert!(dot.contains("  \"AA\" -- \"CC\" [dir=forward];\n"));
+        assert!(dot.contains("  \"DD\" -- \"AA\" [dir=forward];\n"));
+    }
+
+    /// Tests the plan for the Part 1 example against the walkthrough in the problem statement.
+    #[test]
+    fn test_day16_plan_part1_t001() {
+        let input = process_input_file("./input/test/day16_t001.txt");
+        let optimiser = get_valve_optimiser(&input, START_VALVE, PART1_MINUTES);
+        let plan = get_valve_plan(&optimiser, PART1_AGENTS);
+        assert_eq!(1651, plan.total_pressure());
+        let stops = plan.agents()[0].stops();
+        let order = stops.iter().map(|s| s.valve()).collect::<Vec<&str>>();
+        assert_eq!(vec!["DD", "BB", "JJ", "HH", "EE", "CC"], order);
+        assert_eq!(
+            (1, 2, 560),
+            (
+                stops[0].arrival_minute(),
+                stops[0].open_minute(),
+                stops[0].cumulative_pressure()
+            )
+        );
+        assert_eq!(
+            (4, 5, 885),
+            (
+                stops[1].arrival_minute(),
+                stops[1].open_minute(),
+                stops[1].cumulative_pressure()
+            )
+        );
+        assert_eq!(vec!["CC", "BB"], stops[1].moves());
+        let log = plan.render_log();
+        assert!(log.starts_with("== Minute 1 ==\nNo valves are open.\nYou move to valve DD.\n\n"));
+        assert!(log.contains(
+            "== Minute 5 ==\nValve DD is open, releasing 20 pressure.\nYou open valve BB.\n"
+        ));
+        assert!(log.contains("== Minute 10 ==\nValves BB, DD, and JJ are open, releasing 54 pressure.\nYou move to valve II.\n"));
+        assert!(log.ends_with(
+            "== Minute 30 ==\nValves BB, CC, DD, EE, HH, and JJ are open, releasing 81 pressure.\n"
+        ));
+        assert_eq!(30, log.matches("== Minute").count());
+    }
+
+    /// Tests that the plan for the Part 2 example splits the valves between the agents, and that
+    /// each plan agrees with the maximum pressure found by the optimiser.
+    #[test]
+    fn test_day16_plan_part2_t001() {
+        let input = process_input_file("./input/test/day16_t001.txt");
+        let optimiser = get_valve_optimiser(&input, START_VALVE, PART2_MINUTES);
+        let plan = get_valve_plan(&optimiser, PART2_AGENTS);
+        assert_eq!(1707, plan.total_pressure());
+        let mut valves = plan
+            .agents()
+            .iter()
+            .flat_map(|agent| agent.stops().iter().map(|s| s.valve()))
+            .collect::<Vec<&str>>();
+        valves.sort();
+        assert_eq!(vec!["BB", "CC", "DD", "EE", "HH", "JJ"], valves);
+        let log = plan.render_log();
+        assert!(log.starts_with("== Minute 1 ==\nNo valves are open.\nYou move to valve "));
+        assert!(log.contains("== Minute 2 ==\nNo valves are open.\nYou open valve DD.\n"));
+        assert!(log.contains("The elephant opens valve JJ."));
+        for agents in 0..=3 {
+            let plan = get_valve_plan(&optimiser, agents);
+            assert_eq!(agents, plan.agents().len());
+            assert_eq!(Ok(plan.total_pressure()), optimiser.max_pressure(agents));
+        }
+    }
+
+    /// Tests the JSON export of the plan for the Part 1 example.
+    #[test]
+    fn test_day16_plan_json_t001() {
+        let input = process_input_file("./input/test/day16_t001.txt");
+        let optimiser = get_valve_optimiser(&input, START_VALVE, PART1_MINUTES);
+        let plan = get_valve_plan(&optimiser, PART1_AGENTS);
+        let json = plan.to_json();
+        assert_eq!(
+            Ok(json.clone()),
+            json.to_pretty_string().parse::<JsonValue>()
+        );
+        let compact = json.to_string();
+        assert!(compact.starts_with("{\"start\":\"AA\",\"minutes\":30,\"total_pressure\":1651,\"agents\":[{\"agent\":0,\"pressure\":1651,\"valves\":[{\"valve\":\"DD\",\"flow_rate\":20,\"arrival_minute\":1,\"open_minute\":2,\"cumulative_pressure\":560}"));
+    }
+
+    /// Tests the parsing of the command line arguments selecting the plan formats.
+    #[test]
+    fn test_day16_plan_args() {
+        let args = |s: &str| {
+            s.split_whitespace()
+                .map(String::from)
+                .collect::<Vec<String>>()
+        };
+        assert_eq!(Ok(vec![]), parse_plan_args(&args("")));
+        assert_eq!(
+            Ok(vec![PlanFormat::Log, PlanFormat::Json]),
+            parse_plan_args(&args("--plan log --plan json"))
+        );
+        assert!(parse_plan_args(&args("--plan")).is_err());
+        assert!(parse_plan_args(&args("--plan xml")).is_err());
+        assert!(parse_plan_args(&args("log")).is_err());
+        assert_eq!(
+            Ok(vec![PlanFormat::CompressedDot, PlanFormat::TunnelDot]),
+            parse_plan_args(&args("--dot compressed --dot tunnels"))
+        );
+        assert!(parse_plan_args(&args("--dot log")).is_err());
+    }
+
+    /// Tests the DOT export of the tunnel network for the example input, with and without the plan
+    /// for Part 1 highlighted.
+    #[test]
+    fn test_day16_tunnel_dot_t001() {
+        let input = process_input_file("./input/test/day16_t001.txt");
+        let dot = render_tunnel_dot(&input, None);
+        assert!(
+            dot.starts_with("graph tunnels {\n  \"AA\" [label=\"AA\\nflow 0\", shape=circle];\n")
+        );
+        assert!(dot.ends_with("}\n"));
+        assert!(dot.contains("  \"BB\" [label=\"BB\\nflow 13\", shape=circle];\n"));
+        // Tunnels are given from both ends in the scan, but only appear once
+        assert_eq!(10, dot.matches(" -- ").count());
+        assert!(dot.contains("  \"AA\" -- \"DD\";\n"));
+        assert!(!dot.contains("\"DD\" -- \"AA\""));
+        assert!(!dot.contains("color="));
+        let optimiser = get_valve_optimiser(&input, START_VALVE, PART1_MINUTES);
+        let plan = get_valve_plan(&optimiser, PART1_AGENTS);
+        let dot = render_plan(&input, &optimiser, &plan, PlanFormat::TunnelDot);
+        assert!(dot.contains("  \"AA\" [label=\"AA\\nflow 0\", shape=doublecircle];\n"));
+        assert!(dot.contains("  \"AA\" -- \"DD\" [color=red, penwidth=2];\n"));
+        assert!(dot
+            .contains("  \"JJ\" [label=\"JJ\\nflow 21\", shape=circle, color=red, penwidth=2];\n"));
+        // The route passes along every tunnel in the example
+        assert_eq!(10, dot.matches("\" [color=red, penwidth=2];").count());
+    }
+
+    /// Tests the DOT export of the compressed graph for the example input, with the plan for Part 2
+    /// highlighted.
+    #[test]
+    fn test_day16_compressed_dot_t001() {
+        let input = process_input_file("./input/test/day16_t001.txt");
+        let graph = input.compress(START_VALVE).unwrap();
+        let dot = render_compressed_dot(&input, &graph, None);
+        assert!(dot.starts_with(
+            "graph compressed {\n  \"AA\" [label=\"AA\\nflow 0\", shape=doublecircle];\n"
+        ));
+        // Only the start valve and the 6 valves worth opening appear, with every pair joined
+        assert_eq!(7, dot.matches("\\nflow ").count());
+        assert!(!dot.contains("\"II\""));
+        assert_eq!(21, dot.matches(" -- ").count());
+        assert!(dot.contains("  \"AA\" -- \"JJ\" [label=\"3\"];\n"));
+        assert!(dot.contains("  \"HH\" -- \"JJ\" [label=\"8\"];\n"));
+        let optimiser = get_valve_optimiser(&input, START_VALVE, PART2_MINUTES);
+        let plan = get_valve_plan(&optimiser, PART2_AGENTS);
+        let dot = render_plan(&input, &optimiser, &plan, PlanFormat::CompressedDot);
+        assert!(dot.contains("  \"AA\" -- \"DD\" [label=\"2\", color=red, penwidth=2];\n"));
+        assert!(dot.contains("  \"AA\" -- \"JJ\" [label=\"3\", color=blue, penwidth=2];\n"));
+        assert!(dot.contains(
+            "  \"BB\" [label=\"BB\\nflow 13\", shape=circle, color=blue, penwidth=2];\n"
+        ));
+        assert_eq!(6, dot.matches("\", color=").count());
+    }
 }
@@ -81,7 +81,7 @@ fn process_input_file(filename: &str) -> Vec<char> {
     // Read contents of problem input file
     let raw_input = fs::read_to_string(filename).unwrap();
     // Process input file contents into data structure
-    return raw_input.trim().chars().collect::<Vec<char>>();
+    raw_input.trim().chars().collect::<Vec<char>>()
 }
 
 /// Solves AOC 2022 Day 17 Part 1 // Simulates sequence of 2022 rocks falling and returns the
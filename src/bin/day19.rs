@@ -296,7 +296,6 @@ fn simulate_blueprint_recursive(
             None => (),
         }
         // Collect resources
-        let mut resource_total = resource_total;
         resource_total.ore += robot_total.ore;
         resource_total.clay += robot_total.clay;
         resource_total.obsidian += robot_total.obsidian;
@@ -123,7 +123,7 @@ fn mix_values(input_values: &[i64], rounds: u64) -> Vec<i64> {
 }
 
 /// Calculates the new index for the value at the given cursor location.
-fn calculate_new_index(cursor: usize, values: &Vec<(usize, i64)>) -> usize {
+fn calculate_new_index(cursor: usize, values: &[(usize, i64)]) -> usize {
     let cursor_signed = cursor as i64;
     let temp_index = (cursor_signed + values[cursor].1) % (values.len() - 1) as i64;
     if temp_index < 0 {
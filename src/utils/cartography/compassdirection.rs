@@ -1,5 +1,5 @@
 /// Represents the eight compass directions including the cardinal and inter-cardinal directions.
-#[derive(Copy, Clone, PartialEq, Eq)]
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
 pub enum CompassDirection {
     North,
     NorthEast,
@@ -9,7 +9,7 @@ pub struct Point2D {
 
 impl Point2D {
     /// Creates a new 2D point.
-    pub fn new(x: i64, y: i64) -> Self {
+    pub const fn new(x: i64, y: i64) -> Self {
         Self { x, y }
     }
 
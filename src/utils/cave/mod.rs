@@ -0,0 +1,3 @@
+mod sand;
+
+pub use self::sand::{parse_rock_paths, CaveTile, GrainOutcome, PourEnd, PourReport, SandCave};
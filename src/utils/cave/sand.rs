@@ -0,0 +1,267 @@
+use std::collections::HashMap;
+
+use crate::utils::cartography::Point2D;
+
+/// Represents a single tile type in the cave map.
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub enum CaveTile {
+    Rock,
+    Sand,
+}
+
+/// Result of dropping a single grain of sand into the cave.
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub enum GrainOutcome {
+    Rest { loc: Point2D },
+    Abyss,
+    Blocked,
+}
+
+/// Reason that pouring sand into the cave stopped.
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub enum PourEnd {
+    Abyss { source: Point2D },
+    SourcesBlocked,
+    GrainLimit,
+}
+
+/// Summary of pouring sand into the cave.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct PourReport {
+    grains_at_rest: usize,
+    end: PourEnd,
+    blocked_sources: Vec<(Point2D, usize)>,
+}
+
+impl PourReport {
+    /// Gets the total number of grains of sand at rest in the cave.
+    pub fn grains_at_rest(&self) -> usize {
+        self.grains_at_rest
+    }
+
+    /// Gets the reason that pouring stopped.
+    pub fn end(&self) -> PourEnd {
+        self.end
+    }
+
+    /// Gets the sources that became blocked, in the order they became blocked, each with the total
+    /// number of grains at rest in the cave when it became blocked.
+    pub fn blocked_sources(&self) -> &[(Point2D, usize)] {
+        &self.blocked_sources
+    }
+}
+
+/// Simulates sand pouring into a cave from one or more sources. Sand falls down if possible, then
+/// diagonally down-left, then diagonally down-right, otherwise it comes to rest.
+#[derive(Clone, Debug)]
+pub struct SandCave {
+    tiles: HashMap<Point2D, CaveTile>,
+    sources: Vec<Point2D>,
+    max_rock_y: i64,
+    floor_y: Option<i64>,
+    grains_at_rest: usize,
+    // Fall path of the previous grain from each source, with the index of each point in the path
+    fall_paths: Vec<Vec<Point2D>>,
+    fall_path_indices: Vec<HashMap<Point2D, usize>>,
+    blocked: Vec<Option<usize>>,
+}
+
+impl SandCave {
+    /// Creates a new cave from the rock paths, with sand poured from the sources. If floor_offset
+    /// is given, the cave has an infinite floor that many tiles below the lowest rock. Otherwise,
+    /// sand falling below the lowest rock flows into the abyss.
+    pub fn new(
+        rock_paths: &[Vec<Point2D>],
+        sources: &[Point2D],
+        floor_offset: Option<i64>,
+    ) -> Result<SandCave, String> {
+        if sources.is_empty() {
+            return Err(String::from("at least one sand source is needed"));
+        }
+        let mut tiles: HashMap<Point2D, CaveTile> = HashMap::new();
+        for (i, path) in rock_paths.iter().enumerate() {
+            for segment in path.windows(2) {
+                let (from, to) = (segment[0], segment[1]);
+                if from.x() != to.x() && from.y() != to.y() {
+                    return Err(format!(
+                        "rock path {}: segment from {},{} to {},{} is not horizontal or vertical",
+                        i + 1,
+                        from.x(),
+                        from.y(),
+                        to.x(),
+                        to.y()
+                    ));
+                }
+                for x in from.x().min(to.x())..=from.x().max(to.x()) {
+                    for y in from.y().min(to.y())..=from.y().max(to.y()) {
+                        tiles.insert(Point2D::new(x, y), CaveTile::Rock);
+                    }
+                }
+            }
+            if let [point] = path[..] {
+                tiles.insert(point, CaveTile::Rock);
+            }
+        }
+        let max_rock_y = tiles.keys().map(|loc| loc.y()).max().unwrap_or(0);
+        let floor_y = floor_offset.map(|offset| max_rock_y + offset);
+        for source in sources {
+            if tiles.contains_key(source) || floor_y.is_some_and(|floor| source.y() >= floor) {
+                return Err(format!(
+                    "sand source {},{} is inside rock",
+                    source.x(),
+                    source.y()
+                ));
+            }
+        }
+        Ok(SandCave {
+            tiles,
+            sources: sources.to_vec(),
+            max_rock_y,
+            floor_y,
+            grains_at_rest: 0,
+            fall_paths: sources.iter().map(|source| vec![*source]).collect(),
+            fall_path_indices: sources
+                .iter()
+                .map(|source| HashMap::from([(*source, 0)]))
+                .collect(),
+            blocked: vec![None; sources.len()],
+        })
+    }
+
+    /// Gets the sand sources.
+    pub fn sources(&self) -> &[Point2D] {
+        &self.sources
+    }
+
+    /// Gets the y-coordinate of the floor, if the cave has one.
+    pub fn floor_y(&self) -> Option<i64> {
+        self.floor_y
+    }
+
+    /// Gets the number of grains of sand at rest in the cave.
+    pub fn grains_at_rest(&self) -> usize {
+        self.grains_at_rest
+    }
+
+    /// Gets the tile at the given location, or None if the location is empty. The floor is not
+    /// included.
+    pub fn tile(&self, loc: &Point2D) -> Option<CaveTile> {
+        self.tiles.get(loc).copied()
+    }
+
+    /// Drops a single grain of sand from the source with the given index. The grain resumes the
+    /// fall path of the previous grain from the same source at the deepest point that is still
+    /// empty, since the grain would follow the same path to get there.
+    pub fn drop_grain(&mut self, source: usize) -> GrainOutcome {
+        if self.tiles.contains_key(&self.sources[source]) {
+            return GrainOutcome::Blocked;
+        }
+        let path = &mut self.fall_paths[source];
+        let indices = &mut self.fall_path_indices[source];
+        loop {
+            let loc = *path.last().unwrap();
+            if self.floor_y.is_none() && loc.y() > self.max_rock_y {
+                return GrainOutcome::Abyss;
+            }
+            let next = [(0, 1), (-1, 1), (1, 1)]
+                .iter()
+                .map(|(dx, dy)| loc.peek_move_point(*dx, *dy))
+                .find(|next| {
+                    !self.tiles.contains_key(next) && self.floor_y.is_none_or(|f| next.y() < f)
+                });
+            match next {
+                Some(next) => {
+                    indices.insert(next, path.len());
+                    path.push(next);
+                }
+                None => {
+                    self.rest_grain(loc);
+                    return GrainOutcome::Rest { loc };
+                }
+            }
+        }
+    }
+
+    /// Pours sand from the sources in turn until sand falls into the abyss, all of the sources are
+    /// blocked, or the grain limit (if given) has been reached.
+    pub fn pour(&mut self, grain_limit: Option<usize>) -> PourReport {
+        let mut blocked_sources: Vec<(Point2D, usize)> = vec![];
+        let end = 'pour: loop {
+            for source in 0..self.sources.len() {
+                if self.blocked[source].is_some() {
+                    continue;
+                }
+                if grain_limit.is_some_and(|limit| self.grains_at_rest >= limit) {
+                    break 'pour PourEnd::GrainLimit;
+                }
+                match self.drop_grain(source) {
+                    GrainOutcome::Abyss => {
+                        break 'pour PourEnd::Abyss {
+                            source: self.sources[source],
+                        }
+                    }
+                    GrainOutcome::Rest { .. } | GrainOutcome::Blocked => (),
+                }
+                // Check if the sand has blocked the source (possibly sand from another source)
+                for (i, loc) in self.sources.iter().enumerate() {
+                    if self.blocked[i].is_none() && self.tiles.contains_key(loc) {
+                        self.blocked[i] = Some(self.grains_at_rest);
+                        blocked_sources.push((*loc, self.grains_at_rest));
+                    }
+                }
+            }
+            if self.blocked.iter().all(|b| b.is_some()) {
+                break PourEnd::SourcesBlocked;
+            }
+        };
+        PourReport {
+            grains_at_rest: self.grains_at_rest,
+            end,
+            blocked_sources,
+        }
+    }
+
+    /// Brings a grain of sand to rest at the location, cutting the fall path of any source that
+    /// passes through the location.
+    fn rest_grain(&mut self, loc: Point2D) {
+        self.tiles.insert(loc, CaveTile::Sand);
+        self.grains_at_rest += 1;
+        for (path, indices) in self
+            .fall_paths
+            .iter_mut()
+            .zip(self.fall_path_indices.iter_mut())
+        {
+            if let Some(&cut) = indices.get(&loc) {
+                for removed in path.drain(cut..) {
+                    indices.remove(&removed);
+                }
+            }
+        }
+    }
+}
+
+/// Parses the rock paths from the scan of the cave, with one path per line given as points joined
+/// by " -> " (e.g. "498,4 -> 498,6 -> 496,6").
+pub fn parse_rock_paths(s: &str) -> Result<Vec<Vec<Point2D>>, String> {
+    let mut paths: Vec<Vec<Point2D>> = vec![];
+    for (i, line) in s.lines().enumerate() {
+        let line = line.trim();
+        if line.is_empty() {
+            continue;
+        }
+        let mut path: Vec<Point2D> = vec![];
+        for point in line.split("->") {
+            let coords = point
+                .trim()
+                .split(',')
+                .map(|v| v.trim().parse::<i64>())
+                .collect::<Vec<_>>();
+            match coords[..] {
+                [Ok(x), Ok(y)] => path.push(Point2D::new(x, y)),
+                _ => return Err(format!("line {}: bad point \"{}\"", i + 1, point.trim())),
+            }
+        }
+        paths.push(path);
+    }
+    Ok(paths)
+}
@@ -0,0 +1,81 @@
+use std::io::{self, BufReader, Read};
+
+/// Finds the index of the first marker (sequence of characters that are different) with the given
+/// length in the signal read from the reader. Index is the number of characters from the start of
+/// the signal to the end of the marker (inclusive). Reading stops as soon as the marker is found.
+/// Returns an error if the marker length is 0.
+pub fn find_marker_index<R: Read>(reader: R, marker_len: usize) -> io::Result<Option<usize>> {
+    let mut detector = MarkerDetector::new(marker_len).map_err(invalid_marker_len)?;
+    for byte in BufReader::new(reader).bytes() {
+        if let Some(index) = detector.push(byte?) {
+            return Ok(Some(index));
+        }
+    }
+    Ok(None)
+}
+
+/// Finds the indices of every marker with the given length in the signal read from the reader.
+/// Markers may overlap, so a run of distinct characters longer than the marker length reports a
+/// marker ending at each character after the first full marker. Returns an error if the marker
+/// length is 0.
+pub fn find_all_marker_indices<R: Read>(reader: R, marker_len: usize) -> io::Result<Vec<usize>> {
+    let mut detector = MarkerDetector::new(marker_len).map_err(invalid_marker_len)?;
+    let mut indices: Vec<usize> = vec![];
+    for byte in BufReader::new(reader).bytes() {
+        if let Some(index) = detector.push(byte?) {
+            indices.push(index);
+        }
+    }
+    Ok(indices)
+}
+
+/// Converts an error creating a marker detector into an I/O error for the marker search.
+fn invalid_marker_len(e: String) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidInput, e)
+}
+
+/// Sliding-window detector for markers of distinct characters. The position each character was
+/// last seen at is recorded so that each character of the signal is processed in constant time.
+pub struct MarkerDetector {
+    marker_len: usize,
+    last_seen: [Option<usize>; 256],
+    window_start: usize,
+    chars_read: usize,
+}
+
+impl MarkerDetector {
+    /// Creates a new detector for markers of the given length. Returns an error if the marker
+    /// length is 0, since an empty marker cannot be detected.
+    pub fn new(marker_len: usize) -> Result<Self, String> {
+        if marker_len == 0 {
+            return Err(String::from("marker length must be at least 1"));
+        }
+        Ok(Self {
+            marker_len,
+            last_seen: [None; 256],
+            window_start: 0,
+            chars_read: 0,
+        })
+    }
+
+    /// Processes the next character of the signal. Line breaks are ignored. Returns the index of
+    /// the end of the marker if the character completes one.
+    pub fn push(&mut self, c: u8) -> Option<usize> {
+        if c == b'\n' || c == b'\r' {
+            return None;
+        }
+        let position = self.chars_read;
+        self.chars_read += 1;
+        // Shrink the window so it starts after the previous occurrence of this character
+        if let Some(last) = self.last_seen[c as usize] {
+            if last >= self.window_start {
+                self.window_start = last + 1;
+            }
+        }
+        self.last_seen[c as usize] = Some(position);
+        if position + 1 - self.window_start >= self.marker_len {
+            return Some(self.chars_read);
+        }
+        None
+    }
+}
@@ -0,0 +1,3 @@
+mod marker;
+
+pub use self::marker::{find_all_marker_indices, find_marker_index, MarkerDetector};
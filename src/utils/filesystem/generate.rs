@@ -0,0 +1,209 @@
+use std::collections::VecDeque;
+use std::fs;
+use std::io;
+use std::path::Path;
+
+use regex::Regex;
+
+use super::FsTree;
+
+/// Order in which directories are visited when generating a terminal transcript.
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub enum TraversalOrder {
+    DepthFirst,
+    BreadthFirst,
+}
+
+/// Options controlling how a terminal transcript is generated from a file system tree.
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub struct TranscriptOptions {
+    order: TraversalOrder,
+    root_jumps: bool,
+    repeat_listings: bool,
+}
+
+impl TranscriptOptions {
+    /// Creates new transcript options. If root_jumps is set, the session moves between directories
+    /// by jumping back to the root with "cd /" rather than stepping up with "cd ..". If
+    /// repeat_listings is set, each directory is listed twice.
+    pub fn new(order: TraversalOrder, root_jumps: bool, repeat_listings: bool) -> Self {
+        Self {
+            order,
+            root_jumps,
+            repeat_listings,
+        }
+    }
+}
+
+impl Default for TranscriptOptions {
+    fn default() -> Self {
+        Self::new(TraversalOrder::DepthFirst, false, false)
+    }
+}
+
+/// Generates a terminal transcript in the AOC 2022 Day 7 format that lists every directory in the
+/// tree. Entries in each listing are given in the order they were added to the tree.
+pub fn generate_transcript(tree: &FsTree, options: &TranscriptOptions) -> String {
+    let mut lines: Vec<String> = vec![String::from("$ cd /")];
+    // Path of directory indices from the root to the current directory
+    let mut cwd: Vec<usize> = vec![tree.root()];
+    for dir in visit_order(tree, options.order) {
+        let target = path_to(tree, dir);
+        // Move up to the deepest common ancestor (or the root), then down to the target
+        let common = cwd
+            .iter()
+            .zip(target.iter())
+            .take_while(|(a, b)| a == b)
+            .count();
+        if common < cwd.len() {
+            if options.root_jumps && common < cwd.len() - 1 {
+                lines.push(String::from("$ cd /"));
+                cwd.truncate(1);
+            } else {
+                for _ in common..cwd.len() {
+                    lines.push(String::from("$ cd .."));
+                }
+                cwd.truncate(common);
+            }
+        }
+        for id in target.iter().skip(cwd.len()) {
+            lines.push(format!("$ cd {}", tree.node(*id).name()));
+        }
+        cwd = target;
+        let listings = if options.repeat_listings { 2 } else { 1 };
+        for _ in 0..listings {
+            lines.push(String::from("$ ls"));
+            for child in tree.node(dir).children() {
+                let node = tree.node(*child);
+                match node.file_size() {
+                    Some(size) => lines.push(format!("{} {}", size, node.name())),
+                    None => lines.push(format!("dir {}", node.name())),
+                }
+            }
+        }
+    }
+    lines.join("\n")
+}
+
+/// Gets the directories of the tree in the order they are visited.
+fn visit_order(tree: &FsTree, order: TraversalOrder) -> Vec<usize> {
+    let mut visited: Vec<usize> = vec![];
+    let mut pending: VecDeque<usize> = VecDeque::from([tree.root()]);
+    while let Some(dir) = match order {
+        TraversalOrder::DepthFirst => pending.pop_back(),
+        TraversalOrder::BreadthFirst => pending.pop_front(),
+    } {
+        visited.push(dir);
+        let subdirs = tree
+            .node(dir)
+            .children()
+            .iter()
+            .copied()
+            .filter(|id| tree.node(*id).is_dir());
+        match order {
+            // Push in reverse so that the first subdirectory is visited first
+            TraversalOrder::DepthFirst => pending.extend(subdirs.rev()),
+            TraversalOrder::BreadthFirst => pending.extend(subdirs),
+        }
+    }
+    visited
+}
+
+/// Gets the indices of the directories from the root down to the given node (inclusive).
+fn path_to(tree: &FsTree, id: usize) -> Vec<usize> {
+    let mut path = vec![id];
+    while let Some(parent) = tree.node(path[path.len() - 1]).parent() {
+        path.push(parent);
+    }
+    path.reverse();
+    path
+}
+
+/// Builds a file system tree by walking the directory on disk. Entries are added in name order and
+/// symbolic links are skipped. Returns an error for entry names that a transcript cannot hold -
+/// names that are not valid UTF-8, contain a line break, or start or end with whitespace.
+pub fn tree_from_dir(path: &Path) -> io::Result<FsTree> {
+    let mut tree = FsTree::new();
+    let root = tree.root();
+    add_dir_entries(&mut tree, root, path)?;
+    Ok(tree)
+}
+
+/// Adds the entries of the directory on disk to the given directory of the tree, recursing into
+/// subdirectories.
+fn add_dir_entries(tree: &mut FsTree, dir: usize, path: &Path) -> io::Result<()> {
+    let mut entries = fs::read_dir(path)?.collect::<io::Result<Vec<fs::DirEntry>>>()?;
+    entries.sort_by_key(|entry| entry.file_name());
+    for entry in entries {
+        let name = match entry.file_name().into_string() {
+            Ok(name) if is_transcript_name(&name) => name,
+            _ => {
+                return Err(io::Error::new(
+                    io::ErrorKind::InvalidData,
+                    format!("{} cannot be named in a transcript", entry.path().display()),
+                ))
+            }
+        };
+        let metadata = fs::symlink_metadata(entry.path())?;
+        let added = if metadata.is_dir() {
+            tree.add_dir(dir, &name).and_then(|id| {
+                add_dir_entries(tree, id, &entry.path()).map_err(|e| e.to_string())?;
+                Ok(id)
+            })
+        } else if metadata.is_file() {
+            tree.add_file(dir, &name, metadata.len())
+        } else {
+            continue;
+        };
+        added.map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
+    }
+    Ok(())
+}
+
+/// Checks if the name can be given in a transcript line and read back unchanged.
+fn is_transcript_name(name: &str) -> bool {
+    !name.contains(['\n', '\r']) && name.trim() == name
+}
+
+/// Builds a file system tree from a synthetic description in the nested list format used by the
+/// AOC 2022 Day 7 problem statement (as produced by FsTree::render_tree). Each level of nesting is
+/// indented by two spaces, and the first line must be the root directory.
+pub fn parse_tree_listing(listing: &str) -> Result<FsTree, String> {
+    let regex_line = Regex::new(r"^( *)- (.+) \((?:(dir)|file, size=(\d+))\)$").unwrap();
+    let mut tree = FsTree::new();
+    // Directory indices for each level of nesting above the current line
+    let mut dirs: Vec<usize> = vec![];
+    for (i, line) in listing.lines().enumerate() {
+        let line = line.trim_end();
+        if line.is_empty() {
+            continue;
+        }
+        let captures = match regex_line.captures(line) {
+            Some(captures) => captures,
+            None => return Err(format!("line {}: bad tree entry \"{}\"", i + 1, line)),
+        };
+        let indent = captures[1].len();
+        let name = &captures[2];
+        if dirs.is_empty() {
+            if indent != 0 || name != "/" || captures.get(3).is_none() {
+                return Err(format!("line {}: expected root directory", i + 1));
+            }
+            dirs.push(tree.root());
+            continue;
+        }
+        if indent % 2 != 0 || indent / 2 == 0 || indent / 2 > dirs.len() {
+            return Err(format!("line {}: bad indentation", i + 1));
+        }
+        dirs.truncate(indent / 2);
+        let parent = dirs[dirs.len() - 1];
+        let added = match captures.get(4) {
+            Some(size) => match size.as_str().parse::<u64>() {
+                Ok(size) => tree.add_file(parent, name, size),
+                Err(_) => Err(format!("file size {} is too large", size.as_str())),
+            },
+            None => tree.add_dir(parent, name).inspect(|id| dirs.push(*id)),
+        };
+        added.map_err(|e| format!("line {}: {}", i + 1, e))?;
+    }
+    Ok(tree)
+}
@@ -0,0 +1,11 @@
+mod generate;
+mod transcript;
+mod tree;
+mod validate;
+
+pub use self::generate::{
+    generate_transcript, parse_tree_listing, tree_from_dir, TranscriptOptions, TraversalOrder,
+};
+pub use self::transcript::{build_tree, parse_session, Command, LsEntry};
+pub use self::tree::{FsNode, FsTree};
+pub use self::validate::{validate_session, SessionAnomaly, SessionReport};
@@ -0,0 +1,114 @@
+use super::FsTree;
+
+/// Represents a single command entered in a terminal session, along with its output.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub enum Command {
+    Cd { target: String },
+    Ls { entries: Vec<LsEntry> },
+}
+
+/// Represents a single line of output from the "ls" command.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub enum LsEntry {
+    Dir { name: String },
+    File { name: String, size: u64 },
+}
+
+impl LsEntry {
+    /// Gets the name of the listed file or directory.
+    pub fn name(&self) -> &str {
+        match self {
+            LsEntry::Dir { name } => name,
+            LsEntry::File { name, .. } => name,
+        }
+    }
+}
+
+/// Parses the commands from a terminal transcript in the AOC 2022 Day 7 format. Returned value is
+/// vector of commands, each paired with the line number (starting from 1) the command was entered
+/// on. Output lines from "ls" commands immediately follow the command line.
+pub fn parse_session(raw_input: &str) -> Result<Vec<(usize, Command)>, String> {
+    let mut session: Vec<(usize, Command)> = vec![];
+    for (i, line) in raw_input.lines().enumerate() {
+        let line_number = i + 1;
+        let line = line.trim();
+        if line.is_empty() {
+            continue;
+        }
+        if let Some(command) = line.strip_prefix('$') {
+            // The target of "cd" is the rest of the line, so directory names can contain spaces
+            let command = command.trim_start();
+            match command.split_once(' ') {
+                Some(("cd", target)) if !target.trim().is_empty() => session.push((
+                    line_number,
+                    Command::Cd {
+                        target: target.trim().to_string(),
+                    },
+                )),
+                None if command == "ls" => {
+                    session.push((line_number, Command::Ls { entries: vec![] }))
+                }
+                _ => {
+                    return Err(format!(
+                        "line {}: unknown command \"{}\"",
+                        line_number, line
+                    ))
+                }
+            }
+            continue;
+        }
+        // Any other line is output from the preceding "ls" command
+        let entries = match session.last_mut() {
+            Some((_, Command::Ls { entries })) => entries,
+            _ => {
+                return Err(format!(
+                    "line {}: output \"{}\" does not follow an ls command",
+                    line_number, line
+                ))
+            }
+        };
+        match line.split_once(' ') {
+            Some(("dir", name)) => entries.push(LsEntry::Dir {
+                name: name.to_string(),
+            }),
+            Some((size, name)) if size.parse::<u64>().is_ok() => entries.push(LsEntry::File {
+                name: name.to_string(),
+                size: size.parse::<u64>().unwrap(),
+            }),
+            _ => return Err(format!("line {}: bad ls output \"{}\"", line_number, line)),
+        }
+    }
+    Ok(session)
+}
+
+/// Builds the file system tree observed in the terminal session. "cd /" returns to the root
+/// directory at any point, "cd .." at the root stays at the root, and changing into a directory
+/// that has not been listed adds it to the tree. Listing a directory more than once merges the
+/// entries seen in each listing.
+pub fn build_tree(session: &[(usize, Command)]) -> Result<FsTree, String> {
+    let mut tree = FsTree::new();
+    let mut cwd = tree.root();
+    for (line_number, command) in session {
+        match command {
+            Command::Cd { target } => match target.as_str() {
+                "/" => cwd = tree.root(),
+                ".." => cwd = tree.node(cwd).parent().unwrap_or(cwd),
+                name => {
+                    cwd = tree
+                        .add_dir(cwd, name)
+                        .map_err(|e| format!("line {}: {}", line_number, e))?
+                }
+            },
+            Command::Ls { entries } => {
+                for entry in entries {
+                    match entry {
+                        LsEntry::Dir { name } => tree.add_dir(cwd, name),
+                        LsEntry::File { name, size } => tree.add_file(cwd, name, *size),
+                    }
+                    .map_err(|e| format!("line {}: {}", line_number, e))?;
+                }
+            }
+        }
+    }
+    Ok(tree)
+}
@@ -0,0 +1,229 @@
+/// Represents a single file or directory held in a file system tree.
+#[derive(Clone, Debug)]
+pub struct FsNode {
+    name: String,
+    parent: Option<usize>,
+    kind: FsNodeKind,
+}
+
+/// Represents the contents of a file system node.
+#[derive(Clone, Debug)]
+enum FsNodeKind {
+    File { size: u64 },
+    Directory { children: Vec<usize> },
+}
+
+impl FsNode {
+    /// Gets the name of the node. The root directory is named "/".
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Gets the index of the parent directory of the node. The root directory has no parent.
+    pub fn parent(&self) -> Option<usize> {
+        self.parent
+    }
+
+    /// Checks if the node is a directory.
+    pub fn is_dir(&self) -> bool {
+        matches!(self.kind, FsNodeKind::Directory { .. })
+    }
+
+    /// Gets the size of the node if it is a file.
+    pub fn file_size(&self) -> Option<u64> {
+        match self.kind {
+            FsNodeKind::File { size } => Some(size),
+            FsNodeKind::Directory { .. } => None,
+        }
+    }
+
+    /// Gets the indices of the nodes held in the directory. Files have no children.
+    pub fn children(&self) -> &[usize] {
+        match &self.kind {
+            FsNodeKind::File { .. } => &[],
+            FsNodeKind::Directory { children } => children,
+        }
+    }
+}
+
+/// Arena-backed file system tree. Nodes are referred to by their index in the arena, with the root
+/// directory at index 0.
+#[derive(Clone, Debug)]
+pub struct FsTree {
+    nodes: Vec<FsNode>,
+}
+
+impl Default for FsTree {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl FsTree {
+    /// Creates a new file system tree containing only the root directory.
+    pub fn new() -> Self {
+        Self {
+            nodes: vec![FsNode {
+                name: String::from("/"),
+                parent: None,
+                kind: FsNodeKind::Directory { children: vec![] },
+            }],
+        }
+    }
+
+    /// Gets the index of the root directory.
+    pub fn root(&self) -> usize {
+        0
+    }
+
+    /// Gets the number of nodes (files and directories) in the tree.
+    pub fn len(&self) -> usize {
+        self.nodes.len()
+    }
+
+    /// Checks if the tree holds nothing other than the root directory.
+    pub fn is_empty(&self) -> bool {
+        self.nodes.len() == 1
+    }
+
+    /// Gets the node at the given index. Panics if the index is not in the tree.
+    pub fn node(&self, id: usize) -> &FsNode {
+        &self.nodes[id]
+    }
+
+    /// Finds the child of the directory with the given name.
+    pub fn child(&self, dir: usize, name: &str) -> Option<usize> {
+        self.nodes[dir]
+            .children()
+            .iter()
+            .copied()
+            .find(|id| self.nodes[*id].name == name)
+    }
+
+    /// Adds a directory with the given name to the parent directory, returning its index. If the
+    /// parent already holds a directory with that name, the existing directory is returned. Returns
+    /// an error if the parent is not a directory or already holds a file with that name.
+    pub fn add_dir(&mut self, parent: usize, name: &str) -> Result<usize, String> {
+        self.add_node(parent, name, FsNodeKind::Directory { children: vec![] })
+    }
+
+    /// Adds a file with the given name and size to the parent directory, returning its index. If
+    /// the parent already holds a file with that name, the existing file is returned unchanged.
+    /// Returns an error if the parent is not a directory or already holds a directory with that
+    /// name.
+    pub fn add_file(&mut self, parent: usize, name: &str, size: u64) -> Result<usize, String> {
+        self.add_node(parent, name, FsNodeKind::File { size })
+    }
+
+    /// Adds a new node to the parent directory, or returns the matching existing node.
+    fn add_node(&mut self, parent: usize, name: &str, kind: FsNodeKind) -> Result<usize, String> {
+        if !self.nodes[parent].is_dir() {
+            return Err(format!("{} is not a directory", self.path(parent)));
+        }
+        let is_dir = matches!(kind, FsNodeKind::Directory { .. });
+        if let Some(existing) = self.child(parent, name) {
+            if self.nodes[existing].is_dir() != is_dir {
+                return Err(format!(
+                    "{} already exists as a {}",
+                    self.path(existing),
+                    if is_dir { "file" } else { "directory" }
+                ));
+            }
+            return Ok(existing);
+        }
+        let id = self.nodes.len();
+        self.nodes.push(FsNode {
+            name: name.to_string(),
+            parent: Some(parent),
+            kind,
+        });
+        if let FsNodeKind::Directory { children } = &mut self.nodes[parent].kind {
+            children.push(id);
+        }
+        Ok(id)
+    }
+
+    /// Gets the full path of the node. Directory paths end with "/".
+    pub fn path(&self, id: usize) -> String {
+        let mut names: Vec<&str> = vec![];
+        let mut cursor = Some(id);
+        while let Some(current) = cursor {
+            if current != self.root() {
+                names.push(&self.nodes[current].name);
+            }
+            cursor = self.nodes[current].parent;
+        }
+        names.reverse();
+        let mut path = format!("/{}", names.join("/"));
+        if self.nodes[id].is_dir() && id != self.root() {
+            path.push('/');
+        }
+        path
+    }
+
+    /// Calculates the total size of the node, including everything held below it if it is a
+    /// directory.
+    pub fn du(&self, id: usize) -> u64 {
+        match &self.nodes[id].kind {
+            FsNodeKind::File { size } => *size,
+            FsNodeKind::Directory { children } => children.iter().map(|c| self.du(*c)).sum(),
+        }
+    }
+
+    /// Calculates the total size of every node in the tree in a single pass, indexed by node.
+    pub fn sizes(&self) -> Vec<u64> {
+        let mut sizes = vec![0; self.nodes.len()];
+        // Children are always added after their parent, so a reverse pass visits them first
+        for id in (0..self.nodes.len()).rev() {
+            if let FsNodeKind::File { size } = self.nodes[id].kind {
+                sizes[id] = size;
+            }
+            if let Some(parent) = self.nodes[id].parent {
+                sizes[parent] += sizes[id];
+            }
+        }
+        sizes
+    }
+
+    /// Finds all nodes matching the predicate, which is given the node and its total size. Returned
+    /// value is vector of the matching node indices and sizes, in the order nodes were added.
+    pub fn find<P>(&self, predicate: P) -> Vec<(usize, u64)>
+    where
+        P: Fn(&FsNode, u64) -> bool,
+    {
+        self.sizes()
+            .into_iter()
+            .enumerate()
+            .filter(|(id, size)| predicate(&self.nodes[*id], *size))
+            .collect::<Vec<(usize, u64)>>()
+    }
+
+    /// Renders the tree in the nested list format used by the AOC 2022 Day 7 problem statement.
+    /// Entries in each directory are sorted by name.
+    pub fn render_tree(&self) -> String {
+        let mut lines: Vec<String> = vec![];
+        self.render_node(self.root(), 0, &mut lines);
+        lines.join("\n")
+    }
+
+    /// Adds the rendered lines for the node and everything below it.
+    fn render_node(&self, id: usize, depth: usize, lines: &mut Vec<String>) {
+        let node = &self.nodes[id];
+        match node.kind {
+            FsNodeKind::File { size } => lines.push(format!(
+                "{}- {} (file, size={})",
+                "  ".repeat(depth),
+                node.name,
+                size
+            )),
+            FsNodeKind::Directory { .. } => {
+                lines.push(format!("{}- {} (dir)", "  ".repeat(depth), node.name));
+                let mut children = node.children().to_vec();
+                children.sort_by(|a, b| self.nodes[*a].name.cmp(&self.nodes[*b].name));
+                for child in children {
+                    self.render_node(child, depth + 1, lines);
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,173 @@
+use std::collections::{BTreeSet, HashMap, HashSet};
+use std::fmt;
+
+use super::{Command, LsEntry};
+
+/// Represents a problem found in a terminal session that makes the observed file system
+/// inconsistent or incomplete.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub enum SessionAnomaly {
+    /// A directory was listed more than once with different contents.
+    InconsistentListing {
+        line: usize,
+        path: String,
+        first_line: usize,
+    },
+    /// The session changed into a directory that was not shown by an earlier listing.
+    UnlistedCd { line: usize, path: String },
+    /// The session tried to move to the parent of the root directory.
+    AboveRoot { line: usize },
+}
+
+impl SessionAnomaly {
+    /// Gets the line number the anomaly was found on.
+    pub fn line(&self) -> usize {
+        match self {
+            SessionAnomaly::InconsistentListing { line, .. } => *line,
+            SessionAnomaly::UnlistedCd { line, .. } => *line,
+            SessionAnomaly::AboveRoot { line } => *line,
+        }
+    }
+}
+
+impl fmt::Display for SessionAnomaly {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            SessionAnomaly::InconsistentListing {
+                line,
+                path,
+                first_line,
+            } => write!(
+                f,
+                "line {}: listing of {} differs from listing on line {}",
+                line, path, first_line
+            ),
+            SessionAnomaly::UnlistedCd { line, path } => write!(
+                f,
+                "line {}: changed into {} which was not shown by ls",
+                line, path
+            ),
+            SessionAnomaly::AboveRoot { line } => {
+                write!(f, "line {}: cd .. above the root directory", line)
+            }
+        }
+    }
+}
+
+/// Result of validating a terminal session.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct SessionReport {
+    anomalies: Vec<SessionAnomaly>,
+    unlisted_dirs: Vec<String>,
+}
+
+impl SessionReport {
+    /// Gets the anomalies found in the session, in line order.
+    pub fn anomalies(&self) -> &[SessionAnomaly] {
+        &self.anomalies
+    }
+
+    /// Gets the paths of the directories that were never listed, in the order they were found.
+    pub fn unlisted_dirs(&self) -> &[String] {
+        &self.unlisted_dirs
+    }
+
+    /// Checks if the session was free of anomalies.
+    pub fn is_valid(&self) -> bool {
+        self.anomalies.is_empty()
+    }
+
+    /// Checks if the directory sizes are fully determined by the session. If any directory was
+    /// never listed, the sizes of it and the directories above it are only lower bounds. If any
+    /// directory was listed with different contents, the listings are merged when building the
+    /// tree, so the sizes can be over-counted.
+    pub fn sizes_determined(&self) -> bool {
+        self.unlisted_dirs.is_empty()
+            && !self
+                .anomalies
+                .iter()
+                .any(|a| matches!(a, SessionAnomaly::InconsistentListing { .. }))
+    }
+}
+
+/// Validates the terminal session, reporting each anomaly with its line number and the directories
+/// that were never listed.
+pub fn validate_session(session: &[(usize, Command)]) -> SessionReport {
+    let mut anomalies: Vec<SessionAnomaly> = vec![];
+    // Directories are identified by their full path
+    let mut cwd: Vec<String> = vec![];
+    let mut listings: HashMap<Vec<String>, (usize, BTreeSet<String>)> = HashMap::new();
+    let mut known_dirs: Vec<Vec<String>> = vec![vec![]];
+    let mut seen_dirs: HashSet<Vec<String>> = HashSet::from([vec![]]);
+    for (line, command) in session {
+        match command {
+            Command::Cd { target } => match target.as_str() {
+                "/" => cwd.clear(),
+                ".." => {
+                    if cwd.pop().is_none() {
+                        anomalies.push(SessionAnomaly::AboveRoot { line: *line });
+                    }
+                }
+                name => {
+                    cwd.push(name.to_string());
+                    if !seen_dirs.contains(&cwd) {
+                        anomalies.push(SessionAnomaly::UnlistedCd {
+                            line: *line,
+                            path: render_path(&cwd),
+                        });
+                        seen_dirs.insert(cwd.clone());
+                        known_dirs.push(cwd.clone());
+                    }
+                }
+            },
+            Command::Ls { entries } => {
+                let contents = entries
+                    .iter()
+                    .map(|entry| match entry {
+                        LsEntry::Dir { name } => format!("dir {}", name),
+                        LsEntry::File { name, size } => format!("{} {}", size, name),
+                    })
+                    .collect::<BTreeSet<String>>();
+                match listings.get(&cwd) {
+                    Some((first_line, first_contents)) if *first_contents != contents => {
+                        anomalies.push(SessionAnomaly::InconsistentListing {
+                            line: *line,
+                            path: render_path(&cwd),
+                            first_line: *first_line,
+                        });
+                    }
+                    Some(_) => (),
+                    None => {
+                        listings.insert(cwd.clone(), (*line, contents));
+                    }
+                }
+                for entry in entries {
+                    if let LsEntry::Dir { name } = entry {
+                        let mut path = cwd.clone();
+                        path.push(name.to_string());
+                        if seen_dirs.insert(path.clone()) {
+                            known_dirs.push(path);
+                        }
+                    }
+                }
+            }
+        }
+    }
+    let unlisted_dirs = known_dirs
+        .iter()
+        .filter(|path| !listings.contains_key(*path))
+        .map(|path| render_path(path))
+        .collect::<Vec<String>>();
+    SessionReport {
+        anomalies,
+        unlisted_dirs,
+    }
+}
+
+/// Renders the directory path in the same format as the file system tree.
+fn render_path(path: &[String]) -> String {
+    if path.is_empty() {
+        return String::from("/");
+    }
+    format!("/{}/", path.join("/"))
+}
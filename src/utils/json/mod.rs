@@ -0,0 +1,3 @@
+mod value;
+
+pub use self::value::JsonValue;
@@ -0,0 +1,360 @@
+use std::fmt;
+use std::str::FromStr;
+
+/// Represents a JSON value. Numbers are kept as their literal text so that integers of any size
+/// are preserved exactly. Object entries are kept in the order they were given.
+#[derive(Clone, PartialEq, Debug)]
+pub enum JsonValue {
+    Null,
+    Bool { value: bool },
+    Number { literal: String },
+    String { value: String },
+    Array { items: Vec<JsonValue> },
+    Object { entries: Vec<(String, JsonValue)> },
+}
+
+impl JsonValue {
+    /// Gets the name of the JSON type of the value, for use in error messages.
+    pub fn type_name(&self) -> &'static str {
+        match self {
+            JsonValue::Null => "null",
+            JsonValue::Bool { .. } => "boolean",
+            JsonValue::Number { .. } => "number",
+            JsonValue::String { .. } => "string",
+            JsonValue::Array { .. } => "array",
+            JsonValue::Object { .. } => "object",
+        }
+    }
+
+    /// Formats the value over multiple lines, with nested values indented by two spaces. Empty
+    /// arrays and objects are kept on a single line.
+    pub fn to_pretty_string(&self) -> String {
+        let mut output = String::new();
+        self.write_pretty(&mut output, 0);
+        output
+    }
+
+    /// Writes the pretty format of the value to the output, at the given indentation depth.
+    fn write_pretty(&self, output: &mut String, depth: usize) {
+        let indent = "  ".repeat(depth + 1);
+        match self {
+            JsonValue::Array { items } if !items.is_empty() => {
+                output.push_str("[\n");
+                for (i, item) in items.iter().enumerate() {
+                    output.push_str(&indent);
+                    item.write_pretty(output, depth + 1);
+                    output.push_str(if i + 1 < items.len() { ",\n" } else { "\n" });
+                }
+                output.push_str(&"  ".repeat(depth));
+                output.push(']');
+            }
+            JsonValue::Object { entries } if !entries.is_empty() => {
+                output.push_str("{\n");
+                for (i, (key, value)) in entries.iter().enumerate() {
+                    output.push_str(&indent);
+                    output.push_str(&quote_string(key));
+                    output.push_str(": ");
+                    value.write_pretty(output, depth + 1);
+                    output.push_str(if i + 1 < entries.len() { ",\n" } else { "\n" });
+                }
+                output.push_str(&"  ".repeat(depth));
+                output.push('}');
+            }
+            _ => output.push_str(&self.to_string()),
+        }
+    }
+}
+
+impl fmt::Display for JsonValue {
+    /// Displays the value as compact JSON, with no whitespace between elements.
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            JsonValue::Null => write!(f, "null"),
+            JsonValue::Bool { value } => write!(f, "{}", value),
+            JsonValue::Number { literal } => write!(f, "{}", literal),
+            JsonValue::String { value } => write!(f, "{}", quote_string(value)),
+            JsonValue::Array { items } => {
+                write!(f, "[")?;
+                for (i, item) in items.iter().enumerate() {
+                    if i > 0 {
+                        write!(f, ",")?;
+                    }
+                    write!(f, "{}", item)?;
+                }
+                write!(f, "]")
+            }
+            JsonValue::Object { entries } => {
+                write!(f, "{{")?;
+                for (i, (key, value)) in entries.iter().enumerate() {
+                    if i > 0 {
+                        write!(f, ",")?;
+                    }
+                    write!(f, "{}:{}", quote_string(key), value)?;
+                }
+                write!(f, "}}")
+            }
+        }
+    }
+}
+
+impl FromStr for JsonValue {
+    type Err = String;
+
+    /// Parses a JSON document. Errors report the position (starting from 1) of the character where
+    /// parsing failed.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let chars = s.chars().collect::<Vec<char>>();
+        let mut cursor = 0;
+        let value = parse_value(&chars, &mut cursor)?;
+        skip_whitespace(&chars, &mut cursor);
+        if cursor != chars.len() {
+            return Err(format!(
+                "unexpected '{}' at position {} after end of JSON value",
+                chars[cursor],
+                cursor + 1
+            ));
+        }
+        Ok(value)
+    }
+}
+
+/// Formats the string as a JSON string literal, escaping characters as needed.
+fn quote_string(s: &str) -> String {
+    let mut output = String::from("\"");
+    for c in s.chars() {
+        match c {
+            '"' => output.push_str("\\\""),
+            '\\' => output.push_str("\\\\"),
+            '\n' => output.push_str("\\n"),
+            '\r' => output.push_str("\\r"),
+            '\t' => output.push_str("\\t"),
+            c if (c as u32) < 0x20 => output.push_str(&format!("\\u{:04x}", c as u32)),
+            c => output.push(c),
+        }
+    }
+    output.push('"');
+    output
+}
+
+/// Moves the cursor past any JSON whitespace.
+fn skip_whitespace(chars: &[char], cursor: &mut usize) {
+    while *cursor < chars.len() && matches!(chars[*cursor], ' ' | '\t' | '\n' | '\r') {
+        *cursor += 1;
+    }
+}
+
+/// Parses the JSON value starting at the cursor.
+fn parse_value(chars: &[char], cursor: &mut usize) -> Result<JsonValue, String> {
+    skip_whitespace(chars, cursor);
+    match chars.get(*cursor) {
+        None => Err(String::from("JSON ended unexpectedly")),
+        Some('[') => {
+            *cursor += 1;
+            let mut items: Vec<JsonValue> = vec![];
+            if next_is(chars, cursor, ']') {
+                return Ok(JsonValue::Array { items });
+            }
+            loop {
+                items.push(parse_value(chars, cursor)?);
+                if next_is(chars, cursor, ']') {
+                    return Ok(JsonValue::Array { items });
+                }
+                expect(chars, cursor, ',')?;
+            }
+        }
+        Some('{') => {
+            *cursor += 1;
+            let mut entries: Vec<(String, JsonValue)> = vec![];
+            if next_is(chars, cursor, '}') {
+                return Ok(JsonValue::Object { entries });
+            }
+            loop {
+                skip_whitespace(chars, cursor);
+                let key = parse_string(chars, cursor)?;
+                expect(chars, cursor, ':')?;
+                entries.push((key, parse_value(chars, cursor)?));
+                if next_is(chars, cursor, '}') {
+                    return Ok(JsonValue::Object { entries });
+                }
+                expect(chars, cursor, ',')?;
+            }
+        }
+        Some('"') => Ok(JsonValue::String {
+            value: parse_string(chars, cursor)?,
+        }),
+        Some('-' | '0'..='9') => parse_number(chars, cursor),
+        Some(_) => {
+            for (word, value) in [
+                ("true", JsonValue::Bool { value: true }),
+                ("false", JsonValue::Bool { value: false }),
+                ("null", JsonValue::Null),
+            ] {
+                let end = *cursor + word.len();
+                if end <= chars.len() && chars[*cursor..end].iter().copied().eq(word.chars()) {
+                    *cursor = end;
+                    return Ok(value);
+                }
+            }
+            Err(format!(
+                "unexpected '{}' at position {}",
+                chars[*cursor],
+                *cursor + 1
+            ))
+        }
+    }
+}
+
+/// Checks if the next non-whitespace character is the given character, moving the cursor past it
+/// if so.
+fn next_is(chars: &[char], cursor: &mut usize, c: char) -> bool {
+    skip_whitespace(chars, cursor);
+    if chars.get(*cursor) == Some(&c) {
+        *cursor += 1;
+        return true;
+    }
+    false
+}
+
+/// Moves the cursor past the next non-whitespace character, returning an error if it is not the
+/// given character.
+fn expect(chars: &[char], cursor: &mut usize, c: char) -> Result<(), String> {
+    if next_is(chars, cursor, c) {
+        return Ok(());
+    }
+    match chars.get(*cursor) {
+        Some(found) => Err(format!(
+            "expected '{}' at position {} but found '{}'",
+            c,
+            *cursor + 1,
+            found
+        )),
+        None => Err(format!("JSON ended unexpectedly, expected '{}'", c)),
+    }
+}
+
+/// Parses a number starting at the cursor, checking it follows the JSON number grammar.
+fn parse_number(chars: &[char], cursor: &mut usize) -> Result<JsonValue, String> {
+    let start = *cursor;
+    let digits = |cursor: &mut usize| {
+        let digits_start = *cursor;
+        while *cursor < chars.len() && chars[*cursor].is_ascii_digit() {
+            *cursor += 1;
+        }
+        *cursor > digits_start
+    };
+    let bad_number = || format!("invalid number at position {}", start + 1);
+    if chars.get(*cursor) == Some(&'-') {
+        *cursor += 1;
+    }
+    if chars.get(*cursor) == Some(&'0') {
+        *cursor += 1;
+    } else if !digits(cursor) {
+        return Err(bad_number());
+    }
+    if chars.get(*cursor) == Some(&'.') {
+        *cursor += 1;
+        if !digits(cursor) {
+            return Err(bad_number());
+        }
+    }
+    if matches!(chars.get(*cursor), Some('e' | 'E')) {
+        *cursor += 1;
+        if matches!(chars.get(*cursor), Some('+' | '-')) {
+            *cursor += 1;
+        }
+        if !digits(cursor) {
+            return Err(bad_number());
+        }
+    }
+    Ok(JsonValue::Number {
+        literal: chars[start..*cursor].iter().collect(),
+    })
+}
+
+/// Parses a string literal starting at the cursor, resolving any escape sequences.
+fn parse_string(chars: &[char], cursor: &mut usize) -> Result<String, String> {
+    if chars.get(*cursor) != Some(&'"') {
+        return Err(format!("expected string at position {}", *cursor + 1));
+    }
+    let start = *cursor;
+    *cursor += 1;
+    let mut value = String::new();
+    loop {
+        let c = match chars.get(*cursor) {
+            Some(c) => *c,
+            None => return Err(format!("unterminated string at position {}", start + 1)),
+        };
+        *cursor += 1;
+        match c {
+            '"' => return Ok(value),
+            '\\' => {
+                let escaped = chars.get(*cursor).copied();
+                *cursor += 1;
+                match escaped {
+                    Some('"') => value.push('"'),
+                    Some('\\') => value.push('\\'),
+                    Some('/') => value.push('/'),
+                    Some('b') => value.push('\u{8}'),
+                    Some('f') => value.push('\u{c}'),
+                    Some('n') => value.push('\n'),
+                    Some('r') => value.push('\r'),
+                    Some('t') => value.push('\t'),
+                    Some('u') => value.push(parse_unicode_escape(chars, cursor)?),
+                    _ => return Err(format!("invalid escape at position {}", *cursor - 1)),
+                }
+            }
+            c if (c as u32) < 0x20 => {
+                return Err(format!(
+                    "control character in string at position {}",
+                    *cursor
+                ))
+            }
+            c => value.push(c),
+        }
+    }
+}
+
+/// Parses the hex digits of a \u escape (the cursor is after the "u"), combining surrogate pairs.
+fn parse_unicode_escape(chars: &[char], cursor: &mut usize) -> Result<char, String> {
+    let hex = |cursor: &mut usize| -> Result<u32, String> {
+        let end = *cursor + 4;
+        let digits = chars
+            .get(*cursor..end)
+            .map(|d| d.iter().collect::<String>())
+            .unwrap_or_default();
+        let code = u32::from_str_radix(&digits, 16)
+            .map_err(|_| format!("invalid unicode escape at position {}", *cursor - 1))?;
+        *cursor = end;
+        Ok(code)
+    };
+    let start = *cursor - 1;
+    let mut code = hex(cursor)?;
+    if (0xd800..0xdc00).contains(&code) {
+        if chars.get(*cursor..*cursor + 2) != Some(&['\\', 'u']) {
+            return Err(format!("unpaired surrogate at position {}", start));
+        }
+        *cursor += 2;
+        let low = hex(cursor)?;
+        if !(0xdc00..0xe000).contains(&low) {
+            return Err(format!("unpaired surrogate at position {}", start));
+        }
+        code = 0x10000 + ((code - 0xd800) << 10) + (low - 0xdc00);
+    }
+    char::from_u32(code).ok_or(format!("invalid unicode escape at position {}", start))
+}
+
+impl From<u64> for JsonValue {
+    fn from(value: u64) -> Self {
+        JsonValue::Number {
+            literal: value.to_string(),
+        }
+    }
+}
+
+impl From<&str> for JsonValue {
+    fn from(value: &str) -> Self {
+        JsonValue::String {
+            value: value.to_string(),
+        }
+    }
+}
@@ -0,0 +1,83 @@
+use super::CrateStack;
+
+/// Represents a crane that can move crates between stacks.
+pub trait Crane {
+    /// Gets the maximum number of crates the crane can lift in a single pick-up. None means there
+    /// is no limit.
+    fn max_lift(&self) -> Option<usize>;
+
+    /// Moves the given quantity of crates from the top of one stack to the top of another. Crates
+    /// lifted together keep their order. Returns an error if the source stack runs out of crates, or
+    /// if the crane cannot lift any crates.
+    fn move_crates(
+        &self,
+        stacks: &mut [CrateStack],
+        quantity: usize,
+        from: usize,
+        to: usize,
+    ) -> Result<(), String> {
+        let lift = self.max_lift().unwrap_or(quantity);
+        if lift == 0 && quantity > 0 {
+            return Err(String::from("crane cannot lift any crates"));
+        }
+        let mut remaining = quantity;
+        while remaining > 0 {
+            let batch = remaining.min(lift);
+            if stacks[from].len() < batch {
+                return Err(format!(
+                    "cannot lift {} crates from stack {} holding {}",
+                    batch,
+                    from + 1,
+                    stacks[from].len()
+                ));
+            }
+            let lifted = stacks[from].split_off(stacks[from].len() - batch);
+            stacks[to].extend(lifted);
+            remaining -= batch;
+        }
+        Ok(())
+    }
+}
+
+/// The CrateMover 9000 moves crates one at a time.
+pub struct CrateMover9000;
+
+impl Crane for CrateMover9000 {
+    fn max_lift(&self) -> Option<usize> {
+        Some(1)
+    }
+}
+
+/// The CrateMover 9001 moves any number of crates at once.
+pub struct CrateMover9001;
+
+impl Crane for CrateMover9001 {
+    fn max_lift(&self) -> Option<usize> {
+        None
+    }
+}
+
+/// A crane that can lift up to a configurable number of crates at once. Larger moves are carried
+/// out as multiple lifts.
+pub struct MaxLiftCrane {
+    max_lift: usize,
+}
+
+impl MaxLiftCrane {
+    /// Creates a new crane that can lift up to the given number of crates at once. Returns an error
+    /// if the crane cannot lift any crates.
+    pub fn new(max_lift: usize) -> Result<Self, String> {
+        if max_lift == 0 {
+            return Err(String::from(
+                "crane must be able to lift at least one crate",
+            ));
+        }
+        Ok(Self { max_lift })
+    }
+}
+
+impl Crane for MaxLiftCrane {
+    fn max_lift(&self) -> Option<usize> {
+        Some(self.max_lift)
+    }
+}
@@ -0,0 +1,10 @@
+mod crane;
+mod replay;
+
+use std::collections::VecDeque;
+
+pub use self::crane::{Crane, CrateMover9000, CrateMover9001, MaxLiftCrane};
+pub use self::replay::{render_stacks, CrateReplay};
+
+/// Represents a single stack of crates, with the top crate at the back.
+pub type CrateStack = VecDeque<char>;
@@ -0,0 +1,104 @@
+use super::{Crane, CrateStack};
+
+/// Replays the move instructions one at a time with a given crane, keeping the state of the stacks
+/// after each move so that moves can be undone. When used as an iterator, the replay stops after
+/// yielding the first error.
+pub struct CrateReplay<'a> {
+    crane: &'a dyn Crane,
+    move_instructions: &'a [(usize, usize, usize)],
+    history: Vec<Vec<CrateStack>>,
+    failed: bool,
+}
+
+impl<'a> CrateReplay<'a> {
+    /// Creates a new replay starting from the initial stacks. Move instructions are given as
+    /// (quantity, from, to) tuples, with stacks indexed from 0.
+    pub fn new(
+        crane: &'a dyn Crane,
+        stacks: &[CrateStack],
+        move_instructions: &'a [(usize, usize, usize)],
+    ) -> Self {
+        Self {
+            crane,
+            move_instructions,
+            history: vec![stacks.to_vec()],
+            failed: false,
+        }
+    }
+
+    /// Gets the number of move instructions that have been applied.
+    pub fn moves_applied(&self) -> usize {
+        self.history.len() - 1
+    }
+
+    /// Gets the current state of the stacks.
+    pub fn current(&self) -> &[CrateStack] {
+        &self.history[self.history.len() - 1]
+    }
+
+    /// Applies the next move instruction. Returns false if all instructions have been applied, or
+    /// an error (leaving the state unchanged) if the move cannot be carried out.
+    pub fn step(&mut self) -> Result<bool, String> {
+        if self.moves_applied() == self.move_instructions.len() {
+            return Ok(false);
+        }
+        let (quantity, from, to) = self.move_instructions[self.moves_applied()];
+        let mut stacks = self.current().to_vec();
+        self.crane
+            .move_crates(&mut stacks, quantity, from, to)
+            .map_err(|e| format!("move {}: {}", self.moves_applied() + 1, e))?;
+        self.history.push(stacks);
+        Ok(true)
+    }
+
+    /// Reverts the most recently applied move instruction. Returns false if no moves have been
+    /// applied.
+    pub fn undo(&mut self) -> bool {
+        if self.moves_applied() == 0 {
+            return false;
+        }
+        self.history.pop();
+        true
+    }
+}
+
+impl Iterator for CrateReplay<'_> {
+    type Item = Result<Vec<CrateStack>, String>;
+
+    /// Applies the next move instruction and yields the resulting state of the stacks.
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.failed {
+            return None;
+        }
+        match self.step() {
+            Ok(true) => Some(Ok(self.current().to_vec())),
+            Ok(false) => None,
+            Err(e) => {
+                self.failed = true;
+                Some(Err(e))
+            }
+        }
+    }
+}
+
+/// Renders the stacks in the same drawing format used by the AOC 2022 Day 5 input, including the
+/// row of stack labels.
+pub fn render_stacks(stacks: &[CrateStack]) -> String {
+    let height = stacks.iter().map(|stack| stack.len()).max().unwrap_or(0);
+    let mut lines: Vec<String> = vec![];
+    for level in (0..height).rev() {
+        let cells = stacks
+            .iter()
+            .map(|stack| match stack.get(level) {
+                Some(c) => format!("[{}]", c),
+                None => String::from("   "),
+            })
+            .collect::<Vec<String>>();
+        lines.push(cells.join(" "));
+    }
+    let labels = (1..=stacks.len())
+        .map(|n| format!("{:^3}", n))
+        .collect::<Vec<String>>();
+    lines.push(labels.join(" "));
+    lines.join("\n")
+}
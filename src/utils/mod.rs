@@ -1,3 +1,13 @@
 // Module for utility code. Can be shared across the solutions for different days.
 pub mod cartography;
+pub mod cave;
+pub mod comms;
+pub mod filesystem;
+pub mod json;
+pub mod logistics;
+pub mod numeric;
+pub mod sensors;
+pub mod signal;
+pub mod terrain;
+pub mod volcano;
 pub mod wildlife;
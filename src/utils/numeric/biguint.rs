@@ -0,0 +1,160 @@
+use std::cmp::Ordering;
+use std::fmt;
+use std::ops::{Add, Mul};
+
+/// Arbitrary-precision unsigned integer. Stored as base 2^32 digits with the least significant
+/// digit first, and no trailing zero digits (so zero has no digits).
+#[derive(Clone, PartialEq, Eq, Hash, Debug, Default)]
+pub struct BigUint {
+    digits: Vec<u32>,
+}
+
+impl BigUint {
+    /// Creates a new big integer with the value zero.
+    pub fn zero() -> Self {
+        Self { digits: vec![] }
+    }
+
+    /// Checks if the value is zero.
+    pub fn is_zero(&self) -> bool {
+        self.digits.is_empty()
+    }
+
+    /// Gets the value as a u64, if it fits.
+    pub fn to_u64(&self) -> Option<u64> {
+        match self.digits[..] {
+            [] => Some(0),
+            [low] => Some(low as u64),
+            [low, high] => Some(((high as u64) << 32) | low as u64),
+            _ => None,
+        }
+    }
+
+    /// Subtracts the other value from the current value. Returns None if the result would be
+    /// negative.
+    pub fn checked_sub(&self, other: &BigUint) -> Option<BigUint> {
+        if *self < *other {
+            return None;
+        }
+        let mut digits: Vec<u32> = Vec::with_capacity(self.digits.len());
+        let mut borrow = 0;
+        for (i, digit) in self.digits.iter().enumerate() {
+            let sub = *other.digits.get(i).unwrap_or(&0) as i64 + borrow;
+            let mut diff = *digit as i64 - sub;
+            borrow = 0;
+            if diff < 0 {
+                diff += 1 << 32;
+                borrow = 1;
+            }
+            digits.push(diff as u32);
+        }
+        Some(BigUint::from_digits(digits))
+    }
+
+    /// Divides the value by a non-zero u64 divisor. Returned value is tuple containing the quotient
+    /// and remainder. Panics if the divisor is zero.
+    pub fn div_rem_u64(&self, divisor: u64) -> (BigUint, u64) {
+        assert!(divisor != 0, "attempt to divide by zero");
+        let divisor = divisor as u128;
+        let mut quotient = vec![0; self.digits.len()];
+        let mut rem: u128 = 0;
+        for i in (0..self.digits.len()).rev() {
+            let current = (rem << 32) | self.digits[i] as u128;
+            quotient[i] = (current / divisor) as u32;
+            rem = current % divisor;
+        }
+        (BigUint::from_digits(quotient), rem as u64)
+    }
+
+    /// Creates a big integer from base 2^32 digits (least significant first), removing any
+    /// trailing zero digits.
+    fn from_digits(mut digits: Vec<u32>) -> Self {
+        while digits.last() == Some(&0) {
+            digits.pop();
+        }
+        Self { digits }
+    }
+}
+
+impl From<u64> for BigUint {
+    fn from(value: u64) -> Self {
+        BigUint::from_digits(vec![value as u32, (value >> 32) as u32])
+    }
+}
+
+impl Ord for BigUint {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.digits
+            .len()
+            .cmp(&other.digits.len())
+            .then_with(|| self.digits.iter().rev().cmp(other.digits.iter().rev()))
+    }
+}
+
+impl PartialOrd for BigUint {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Add for &BigUint {
+    type Output = BigUint;
+
+    fn add(self, other: &BigUint) -> BigUint {
+        let len = self.digits.len().max(other.digits.len());
+        let mut digits: Vec<u32> = Vec::with_capacity(len + 1);
+        let mut carry: u64 = 0;
+        for i in 0..len {
+            let sum = *self.digits.get(i).unwrap_or(&0) as u64
+                + *other.digits.get(i).unwrap_or(&0) as u64
+                + carry;
+            digits.push(sum as u32);
+            carry = sum >> 32;
+        }
+        digits.push(carry as u32);
+        BigUint::from_digits(digits)
+    }
+}
+
+impl Mul for &BigUint {
+    type Output = BigUint;
+
+    fn mul(self, other: &BigUint) -> BigUint {
+        if self.is_zero() || other.is_zero() {
+            return BigUint::zero();
+        }
+        let mut digits = vec![0u32; self.digits.len() + other.digits.len()];
+        for (i, a) in self.digits.iter().enumerate() {
+            let mut carry: u64 = 0;
+            for (j, b) in other.digits.iter().enumerate() {
+                let current = digits[i + j] as u64 + *a as u64 * *b as u64 + carry;
+                digits[i + j] = current as u32;
+                carry = current >> 32;
+            }
+            digits[i + other.digits.len()] = carry as u32;
+        }
+        BigUint::from_digits(digits)
+    }
+}
+
+impl fmt::Display for BigUint {
+    /// Displays the value in decimal.
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if self.is_zero() {
+            return write!(f, "0");
+        }
+        // Extract groups of nine decimal digits, least significant first
+        let mut groups: Vec<u64> = vec![];
+        let mut value = self.clone();
+        while !value.is_zero() {
+            let (quotient, rem) = value.div_rem_u64(1_000_000_000);
+            groups.push(rem);
+            value = quotient;
+        }
+        write!(f, "{}", groups[groups.len() - 1])?;
+        for group in groups.iter().rev().skip(1) {
+            write!(f, "{:09}", group)?;
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,3 @@
+mod biguint;
+
+pub use self::biguint::BigUint;
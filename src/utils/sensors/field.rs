@@ -0,0 +1,274 @@
+use std::collections::HashSet;
+use std::ops::RangeInclusive;
+
+use crate::utils::cartography::Point2D;
+
+/// Characters used to render coverage density, from no coverage to full coverage.
+const DENSITY_RAMP: [char; 10] = [' ', '.', ':', '-', '=', '+', '*', '#', '%', '@'];
+
+/// Represents a sensor and the closest beacon to it. The sensor covers every location within the
+/// Manhattan distance to the beacon (its radius).
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub struct Sensor {
+    loc: Point2D,
+    beacon: Point2D,
+    radius: u64,
+}
+
+impl Sensor {
+    /// Creates a new sensor at the given location with the given closest beacon.
+    pub fn new(loc: Point2D, beacon: Point2D) -> Self {
+        Self {
+            loc,
+            beacon,
+            radius: loc.calculate_manhattan_distance(&beacon),
+        }
+    }
+
+    /// Gets the location of the sensor.
+    pub fn loc(&self) -> Point2D {
+        self.loc
+    }
+
+    /// Gets the location of the closest beacon to the sensor.
+    pub fn beacon(&self) -> Point2D {
+        self.beacon
+    }
+
+    /// Gets the Manhattan distance from the sensor to its closest beacon.
+    pub fn radius(&self) -> u64 {
+        self.radius
+    }
+
+    /// Checks if the sensor covers the given location.
+    pub fn covers(&self, loc: &Point2D) -> bool {
+        self.loc.calculate_manhattan_distance(loc) <= self.radius
+    }
+
+    /// Gets the interval of x-coordinates covered by the sensor in the given row, or None if the
+    /// sensor does not reach the row.
+    pub fn row_interval(&self, y: i64) -> Option<RangeInclusive<i64>> {
+        let reach = self.radius as i64 - (self.loc.y() - y).abs();
+        if reach < 0 {
+            return None;
+        }
+        Some((self.loc.x() - reach)..=(self.loc.x() + reach))
+    }
+}
+
+/// Field of sensors, supporting queries of the locations covered by the sensors. Queries work on
+/// intervals of covered locations in each row, so the cost does not depend on the size of the
+/// sensor coverage.
+#[derive(Clone, Debug)]
+pub struct SensorField {
+    sensors: Vec<Sensor>,
+}
+
+impl SensorField {
+    /// Creates a new sensor field from the sensor locations and their closest beacons.
+    pub fn new(readings: &[(Point2D, Point2D)]) -> Self {
+        Self {
+            sensors: readings
+                .iter()
+                .map(|(loc, beacon)| Sensor::new(*loc, *beacon))
+                .collect(),
+        }
+    }
+
+    /// Gets the sensors in the field.
+    pub fn sensors(&self) -> &[Sensor] {
+        &self.sensors
+    }
+
+    /// Gets the distinct locations of the beacons detected by the sensors.
+    pub fn beacons(&self) -> HashSet<Point2D> {
+        self.sensors.iter().map(|s| s.beacon).collect()
+    }
+
+    /// Gets the covered intervals of x-coordinates in the given row. Intervals are sorted and
+    /// merged, so there is a gap of at least one location between consecutive intervals.
+    pub fn row_intervals(&self, y: i64) -> Vec<RangeInclusive<i64>> {
+        let mut intervals = self
+            .sensors
+            .iter()
+            .filter_map(|s| s.row_interval(y))
+            .collect::<Vec<RangeInclusive<i64>>>();
+        intervals.sort_by_key(|interval| *interval.start());
+        let mut merged: Vec<RangeInclusive<i64>> = vec![];
+        for interval in intervals {
+            match merged.last_mut() {
+                Some(last) if *interval.start() <= *last.end() + 1 => {
+                    if interval.end() > last.end() {
+                        *last = *last.start()..=*interval.end();
+                    }
+                }
+                _ => merged.push(interval),
+            }
+        }
+        merged
+    }
+
+    /// Gets the number of covered locations in the given row.
+    pub fn covered_length(&self, y: i64) -> u64 {
+        self.row_intervals(y)
+            .iter()
+            .map(|interval| (interval.end() - interval.start() + 1) as u64)
+            .sum()
+    }
+
+    /// Checks if the location is covered by any of the sensors.
+    pub fn is_covered(&self, loc: &Point2D) -> bool {
+        self.sensors.iter().any(|s| s.covers(loc))
+    }
+
+    /// Gets the indices of the sensors that cover the location.
+    pub fn covered_by(&self, loc: &Point2D) -> Vec<usize> {
+        (0..self.sensors.len())
+            .filter(|i| self.sensors[*i].covers(loc))
+            .collect()
+    }
+
+    /// Gets the rows from min_y to max_y (inclusive) where the coverage is not contiguous, with a
+    /// gap between two covered intervals.
+    pub fn gapped_rows(&self, min_y: i64, max_y: i64) -> Vec<i64> {
+        (min_y..=max_y)
+            .filter(|y| self.row_intervals(*y).len() > 1)
+            .collect()
+    }
+
+    /// Finds the locations within the box from min to max (inclusive) that are not covered by any
+    /// sensor. The top-left location of each uncovered region (with locations connected to their
+    /// eight neighbours) lies just outside the diamond covered by a sensor on two sides, or on the
+    /// edge of the box. So it is at an intersection of the diagonal lines bounding the diamonds
+    /// (one or two locations outside each diamond), or where those lines cross the edge of the box.
+    /// The uncovered regions are filled out from the uncovered intersections, so every uncovered
+    /// location is found unless the search stops at the limit. At most limit locations are
+    /// returned, since a large box with little coverage has too many uncovered locations to hold.
+    /// Returned locations are sorted by x-coordinate and then y-coordinate.
+    pub fn uncovered_points(&self, min: &Point2D, max: &Point2D, limit: usize) -> Vec<Point2D> {
+        // Lines outside each diamond - rising lines are y = x + a and falling lines are y = -x + b.
+        // Only the lines just outside the diamonds cross the edge of the box at a top-left location.
+        let mut rising: HashSet<i64> = HashSet::new();
+        let mut falling: HashSet<i64> = HashSet::new();
+        let mut edge_rising: HashSet<i64> = HashSet::new();
+        let mut edge_falling: HashSet<i64> = HashSet::new();
+        for sensor in &self.sensors {
+            let (x, y) = (sensor.loc.x(), sensor.loc.y());
+            for outside in [sensor.radius as i64 + 1, sensor.radius as i64 + 2] {
+                rising.insert(y - x - outside);
+                rising.insert(y - x + outside);
+                falling.insert(x + y - outside);
+                falling.insert(x + y + outside);
+            }
+            let outside = sensor.radius as i64 + 1;
+            edge_rising.extend([y - x - outside, y - x + outside]);
+            edge_falling.extend([x + y - outside, x + y + outside]);
+        }
+        // Collect the candidate locations
+        let mut candidates: HashSet<Point2D> = HashSet::new();
+        for a in &rising {
+            for b in &falling {
+                if (b - a) % 2 == 0 {
+                    candidates.insert(Point2D::new((b - a) / 2, (a + b) / 2));
+                }
+            }
+        }
+        for x in [min.x(), max.x()] {
+            for y in [min.y(), max.y()] {
+                candidates.insert(Point2D::new(x, y));
+            }
+            candidates.extend(edge_rising.iter().map(|a| Point2D::new(x, x + a)));
+            candidates.extend(edge_falling.iter().map(|b| Point2D::new(x, b - x)));
+        }
+        for y in [min.y(), max.y()] {
+            candidates.extend(edge_rising.iter().map(|a| Point2D::new(y - a, y)));
+            candidates.extend(edge_falling.iter().map(|b| Point2D::new(b - y, y)));
+        }
+        // Fill out the uncovered regions from the uncovered candidates in the box
+        let is_uncovered = |loc: &Point2D| {
+            (min.x()..=max.x()).contains(&loc.x())
+                && (min.y()..=max.y()).contains(&loc.y())
+                && !self.is_covered(loc)
+        };
+        let mut visit_queue = candidates
+            .into_iter()
+            .filter(is_uncovered)
+            .collect::<Vec<Point2D>>();
+        // Candidates are visited in order, so the locations found before the limit do not depend
+        // on the order of the hash set
+        visit_queue.sort_by_key(|loc| (loc.x(), loc.y()));
+        visit_queue.truncate(limit);
+        let mut uncovered: HashSet<Point2D> = visit_queue.iter().copied().collect();
+        while let Some(loc) = visit_queue.pop() {
+            for dx in -1..=1 {
+                for dy in -1..=1 {
+                    let next = Point2D::new(loc.x() + dx, loc.y() + dy);
+                    if uncovered.len() == limit {
+                        break;
+                    }
+                    if !uncovered.contains(&next) && is_uncovered(&next) {
+                        uncovered.insert(next);
+                        visit_queue.push(next);
+                    }
+                }
+            }
+        }
+        let mut uncovered = uncovered.into_iter().collect::<Vec<Point2D>>();
+        uncovered.sort_by_key(|loc| (loc.x(), loc.y()));
+        uncovered
+    }
+
+    /// Renders a coarse map of the coverage over the box from min to max (inclusive), with the box
+    /// divided into the given number of columns and rows of cells. Each cell is rendered with a
+    /// character showing the fraction of its locations that are covered, from ' ' (none) through
+    /// ".:-=+*#%" to '@' (all).
+    pub fn render_density(
+        &self,
+        min: &Point2D,
+        max: &Point2D,
+        columns: usize,
+        rows: usize,
+    ) -> String {
+        let width = max.x() - min.x() + 1;
+        let height = max.y() - min.y() + 1;
+        let columns = columns.clamp(1, width.max(1) as usize);
+        let rows = rows.clamp(1, height.max(1) as usize);
+        // Boundaries of the cells, with cell i covering from bounds[i] up to bounds[i + 1]
+        let col_bounds = (0..=columns)
+            .map(|i| min.x() + width * i as i64 / columns as i64)
+            .collect::<Vec<i64>>();
+        let row_bounds = (0..=rows)
+            .map(|i| min.y() + height * i as i64 / rows as i64)
+            .collect::<Vec<i64>>();
+        let mut output = String::new();
+        for row in 0..rows {
+            let mut covered = vec![0u64; columns];
+            for y in row_bounds[row]..row_bounds[row + 1] {
+                for interval in self.row_intervals(y) {
+                    for (col, count) in covered.iter_mut().enumerate() {
+                        let start = (*interval.start()).max(col_bounds[col]);
+                        let end = (*interval.end()).min(col_bounds[col + 1] - 1);
+                        if start <= end {
+                            *count += (end - start + 1) as u64;
+                        }
+                    }
+                }
+            }
+            for (col, count) in covered.iter().enumerate() {
+                let area = ((col_bounds[col + 1] - col_bounds[col])
+                    * (row_bounds[row + 1] - row_bounds[row])) as u64;
+                // Any coverage shows as at least the first level, and only full coverage as the last
+                let level = if *count == 0 {
+                    0
+                } else if *count == area {
+                    DENSITY_RAMP.len() - 1
+                } else {
+                    1 + (*count * (DENSITY_RAMP.len() as u64 - 2) / area) as usize
+                };
+                output.push(DENSITY_RAMP[level]);
+            }
+            output.push('\n');
+        }
+        output
+    }
+}
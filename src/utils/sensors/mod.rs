@@ -0,0 +1,3 @@
+mod field;
+
+pub use self::field::{Sensor, SensorField};
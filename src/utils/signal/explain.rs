@@ -0,0 +1,83 @@
+use std::cmp::Ordering;
+
+use super::Packet;
+
+/// Explains the comparison of the left and right packets step by step, in the style of the
+/// distress signal puzzle statement. Each line of the explanation is indented by two spaces for
+/// each level of nesting.
+pub fn explain_comparison(left: &Packet, right: &Packet) -> String {
+    let mut lines: Vec<String> = vec![];
+    let ordering = explain_step(left, right, 0, &mut lines);
+    if ordering == Ordering::Equal {
+        lines.push(String::from(
+            "- Both sides are equal, so the order cannot be decided",
+        ));
+    }
+    lines.join("\n")
+}
+
+/// Adds the explanation lines for comparing the left and right packets at the given depth.
+/// Returned value is the result of the comparison.
+fn explain_step(left: &Packet, right: &Packet, depth: usize, lines: &mut Vec<String>) -> Ordering {
+    let indent = "  ".repeat(depth);
+    lines.push(format!("{}- Compare {} vs {}", indent, left, right));
+    let inner = "  ".repeat(depth + 1);
+    match (left, right) {
+        (Packet::Int { value: l }, Packet::Int { value: r }) => {
+            let ordering = l.cmp(r);
+            match ordering {
+                Ordering::Less => lines.push(format!(
+                    "{}- Left side is smaller, so inputs are in the right order",
+                    inner
+                )),
+                Ordering::Greater => lines.push(format!(
+                    "{}- Right side is smaller, so inputs are not in the right order",
+                    inner
+                )),
+                Ordering::Equal => (),
+            }
+            ordering
+        }
+        (Packet::List { items: l }, Packet::List { items: r }) => {
+            for (left_item, right_item) in l.iter().zip(r.iter()) {
+                let ordering = explain_step(left_item, right_item, depth + 1, lines);
+                if ordering != Ordering::Equal {
+                    return ordering;
+                }
+            }
+            let ordering = l.len().cmp(&r.len());
+            match ordering {
+                Ordering::Less => lines.push(format!(
+                    "{}- Left side ran out of items, so inputs are in the right order",
+                    inner
+                )),
+                Ordering::Greater => lines.push(format!(
+                    "{}- Right side ran out of items, so inputs are not in the right order",
+                    inner
+                )),
+                Ordering::Equal => (),
+            }
+            ordering
+        }
+        (Packet::Int { .. }, Packet::List { .. }) => {
+            let converted = Packet::List {
+                items: vec![left.clone()],
+            };
+            lines.push(format!(
+                "{}- Mixed types; convert left to {} and retry comparison",
+                inner, converted
+            ));
+            explain_step(&converted, right, depth + 1, lines)
+        }
+        (Packet::List { .. }, Packet::Int { .. }) => {
+            let converted = Packet::List {
+                items: vec![right.clone()],
+            };
+            lines.push(format!(
+                "{}- Mixed types; convert right to {} and retry comparison",
+                inner, converted
+            ));
+            explain_step(left, &converted, depth + 1, lines)
+        }
+    }
+}
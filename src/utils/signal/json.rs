@@ -0,0 +1,95 @@
+use std::fs;
+use std::path::Path;
+
+use super::Packet;
+use crate::utils::json::JsonValue;
+
+impl From<&Packet> for JsonValue {
+    /// Converts the packet into a JSON value, with lists as arrays and integers as numbers.
+    fn from(packet: &Packet) -> Self {
+        match packet {
+            Packet::Int { value } => JsonValue::Number {
+                literal: value.to_string(),
+            },
+            Packet::List { items } => JsonValue::Array {
+                items: items.iter().map(JsonValue::from).collect(),
+            },
+        }
+    }
+}
+
+impl TryFrom<&JsonValue> for Packet {
+    type Error = String;
+
+    /// Converts the JSON value into a packet. Only arrays and non-negative integers that fit in
+    /// 64 bits can be converted.
+    fn try_from(value: &JsonValue) -> Result<Self, Self::Error> {
+        packet_from_json(value, "")
+    }
+}
+
+/// Converts the JSON value found at the given path (e.g. "[0][1]") into a packet.
+fn packet_from_json(value: &JsonValue, path: &str) -> Result<Packet, String> {
+    let location = if path.is_empty() {
+        String::new()
+    } else {
+        format!(" (at {})", path)
+    };
+    match value {
+        JsonValue::Array { items } => {
+            let mut packet_items: Vec<Packet> = vec![];
+            for (i, item) in items.iter().enumerate() {
+                packet_items.push(packet_from_json(item, &format!("{}[{}]", path, i))?);
+            }
+            Ok(Packet::List {
+                items: packet_items,
+            })
+        }
+        JsonValue::Number { literal } => match literal.parse::<u64>() {
+            Ok(value) => Ok(Packet::Int { value }),
+            Err(_) => Err(format!(
+                "number {} is not a non-negative 64-bit integer{}",
+                literal, location
+            )),
+        },
+        _ => Err(format!(
+            "{} is not allowed in a packet, only arrays and integers{}",
+            value.type_name(),
+            location
+        )),
+    }
+}
+
+/// Parses a list of packets from a JSON document holding an array of packets. Each packet must be
+/// an array.
+pub fn parse_packets_json(s: &str) -> Result<Vec<Packet>, String> {
+    let items = match s.parse::<JsonValue>()? {
+        JsonValue::Array { items } => items,
+        other => {
+            return Err(format!(
+                "expected an array of packets but found {}",
+                other.type_name()
+            ))
+        }
+    };
+    let mut packets: Vec<Packet> = vec![];
+    for (i, item) in items.iter().enumerate() {
+        if !matches!(item, JsonValue::Array { .. }) {
+            return Err(format!(
+                "packet {}: expected an array but found {}",
+                i + 1,
+                item.type_name()
+            ));
+        }
+        let packet = Packet::try_from(item).map_err(|e| format!("packet {}: {}", i + 1, e))?;
+        packets.push(packet);
+    }
+    Ok(packets)
+}
+
+/// Loads a list of packets from a JSON file holding an array of packets.
+pub fn load_packets_json(path: &Path) -> Result<Vec<Packet>, String> {
+    let raw_input = fs::read_to_string(path)
+        .map_err(|e| format!("could not read {}: {}", path.display(), e))?;
+    parse_packets_json(&raw_input).map_err(|e| format!("{}: {}", path.display(), e))
+}
@@ -0,0 +1,7 @@
+mod explain;
+mod json;
+mod packet;
+
+pub use self::explain::explain_comparison;
+pub use self::json::{load_packets_json, parse_packets_json};
+pub use self::packet::Packet;
@@ -0,0 +1,159 @@
+use std::cmp::Ordering;
+use std::fmt;
+use std::str::FromStr;
+
+/// Represents a single packet of the distress signal. A packet is either a non-negative integer or
+/// a list of zero or more packets.
+#[derive(Clone, Debug)]
+pub enum Packet {
+    Int { value: u64 },
+    List { items: Vec<Packet> },
+}
+
+impl Packet {
+    /// Creates a divider packet, which is a list containing a single list containing the value.
+    pub fn divider(value: u64) -> Packet {
+        Packet::List {
+            items: vec![Packet::List {
+                items: vec![Packet::Int { value }],
+            }],
+        }
+    }
+}
+
+impl Ord for Packet {
+    /// Compares packets using the rules of the distress signal. Integers are compared by value and
+    /// lists are compared item by item, with the shorter list coming first if all of its items match
+    /// the other list. When an integer is compared with a list, the integer is treated as a list
+    /// containing only that integer.
+    fn cmp(&self, other: &Self) -> Ordering {
+        match (self, other) {
+            (Packet::Int { value: left }, Packet::Int { value: right }) => left.cmp(right),
+            (Packet::List { items: left }, Packet::List { items: right }) => {
+                // Lexicographic comparison uses the item ordering then falls back to the lengths
+                left.iter().cmp(right.iter())
+            }
+            (Packet::Int { .. }, Packet::List { items }) => {
+                std::slice::from_ref(self).iter().cmp(items.iter())
+            }
+            (Packet::List { items }, Packet::Int { .. }) => {
+                items.iter().cmp(std::slice::from_ref(other).iter())
+            }
+        }
+    }
+}
+
+impl PartialOrd for Packet {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+/// Packets are equal if neither comes before the other, so an integer is equal to a list that
+/// contains only that integer.
+impl PartialEq for Packet {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for Packet {}
+
+impl fmt::Display for Packet {
+    /// Displays the packet in the format given in the distress signal (e.g. "[1,[2,3],[]]").
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Packet::Int { value } => write!(f, "{}", value),
+            Packet::List { items } => {
+                write!(f, "[")?;
+                for (i, item) in items.iter().enumerate() {
+                    if i > 0 {
+                        write!(f, ",")?;
+                    }
+                    write!(f, "{}", item)?;
+                }
+                write!(f, "]")
+            }
+        }
+    }
+}
+
+impl FromStr for Packet {
+    type Err = String;
+
+    /// Parses a packet from the format given in the distress signal. Whitespace between elements is
+    /// ignored. Errors report the position (starting from 1) of the character where parsing failed.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let chars = s.chars().collect::<Vec<char>>();
+        let mut cursor = 0;
+        let packet = parse_packet(&chars, &mut cursor)?;
+        skip_whitespace(&chars, &mut cursor);
+        if cursor != chars.len() {
+            return Err(format!(
+                "unexpected '{}' at position {} after end of packet",
+                chars[cursor],
+                cursor + 1
+            ));
+        }
+        Ok(packet)
+    }
+}
+
+/// Moves the cursor past any whitespace.
+fn skip_whitespace(chars: &[char], cursor: &mut usize) {
+    while *cursor < chars.len() && chars[*cursor].is_whitespace() {
+        *cursor += 1;
+    }
+}
+
+/// Parses an integer or a list starting at the cursor.
+fn parse_packet(chars: &[char], cursor: &mut usize) -> Result<Packet, String> {
+    skip_whitespace(chars, cursor);
+    match chars.get(*cursor) {
+        None => Err(String::from("packet ended unexpectedly")),
+        Some('[') => {
+            *cursor += 1;
+            let mut items: Vec<Packet> = vec![];
+            skip_whitespace(chars, cursor);
+            if chars.get(*cursor) == Some(&']') {
+                *cursor += 1;
+                return Ok(Packet::List { items });
+            }
+            loop {
+                items.push(parse_packet(chars, cursor)?);
+                skip_whitespace(chars, cursor);
+                match chars.get(*cursor) {
+                    Some(',') => *cursor += 1,
+                    Some(']') => {
+                        *cursor += 1;
+                        return Ok(Packet::List { items });
+                    }
+                    Some(c) => {
+                        return Err(format!(
+                            "expected ',' or ']' at position {} but found '{}'",
+                            *cursor + 1,
+                            c
+                        ))
+                    }
+                    None => return Err(String::from("packet ended before list was closed")),
+                }
+            }
+        }
+        Some(c) if c.is_ascii_digit() => {
+            let start = *cursor;
+            while *cursor < chars.len() && chars[*cursor].is_ascii_digit() {
+                *cursor += 1;
+            }
+            let digits = chars[start..*cursor].iter().collect::<String>();
+            match digits.parse::<u64>() {
+                Ok(value) => Ok(Packet::Int { value }),
+                Err(_) => Err(format!("integer at position {} is too large", start + 1)),
+            }
+        }
+        Some(c) => Err(format!(
+            "expected integer or '[' at position {} but found '{}'",
+            *cursor + 1,
+            c
+        )),
+    }
+}
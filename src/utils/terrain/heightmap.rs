@@ -0,0 +1,181 @@
+use std::collections::hash_map::Entry;
+use std::collections::{HashMap, VecDeque};
+
+use crate::utils::cartography::Point2D;
+
+/// Rule determining if a step can be made between two adjacent points of a heightmap.
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub struct ClimbRule {
+    max_climb: Option<i64>,
+    max_descent: Option<i64>,
+}
+
+impl ClimbRule {
+    /// Creates a new climb rule. A limit of None means the elevation can change by any amount in
+    /// that direction.
+    pub fn new(max_climb: Option<i64>, max_descent: Option<i64>) -> Self {
+        Self {
+            max_climb,
+            max_descent,
+        }
+    }
+
+    /// Checks if a step from the first elevation to the second elevation is allowed.
+    pub fn allows(&self, from: i64, to: i64) -> bool {
+        let change = to - from;
+        self.max_climb.is_none_or(|limit| change <= limit)
+            && self.max_descent.is_none_or(|limit| -change <= limit)
+    }
+}
+
+impl Default for ClimbRule {
+    /// Default rule allows climbing at most one elevation level and descending any amount.
+    fn default() -> Self {
+        Self::new(Some(1), None)
+    }
+}
+
+/// Heightmap of the area surrounding the current position, with the elevations of each point
+/// given from 0 (a) to 25 (z).
+#[derive(Clone, Debug)]
+pub struct Heightmap {
+    elevations: HashMap<Point2D, i64>,
+    width: i64,
+    height: i64,
+    start: Point2D,
+    end: Point2D,
+}
+
+impl Heightmap {
+    /// Parses a heightmap from the puzzle format. The start (S) has elevation a and the end (E)
+    /// has elevation z.
+    pub fn parse(s: &str) -> Result<Heightmap, String> {
+        let mut start: Option<Point2D> = None;
+        let mut end: Option<Point2D> = None;
+        let mut elevations: HashMap<Point2D, i64> = HashMap::new();
+        let mut width: Option<i64> = None;
+        let mut y = 0;
+        for line in s.lines() {
+            let line = line.trim();
+            if line.is_empty() {
+                continue;
+            }
+            for (x, chr) in line.chars().enumerate() {
+                let loc = Point2D::new(x as i64, y);
+                let elevation = match chr {
+                    'S' if start.is_none() => {
+                        start = Some(loc);
+                        0
+                    }
+                    'E' if end.is_none() => {
+                        end = Some(loc);
+                        25
+                    }
+                    'a'..='z' => (chr as i64) - ('a' as i64),
+                    _ => return Err(format!("line {}: bad char '{}'", y + 1, chr)),
+                };
+                elevations.insert(loc, elevation);
+            }
+            let line_width = line.chars().count() as i64;
+            if *width.get_or_insert(line_width) != line_width {
+                return Err(format!("line {}: heightmap is not rectangular", y + 1));
+            }
+            y += 1;
+        }
+        let start = start.ok_or("start point (S) not found")?;
+        let end = end.ok_or("end point (E) not found")?;
+        Ok(Heightmap {
+            elevations,
+            width: width.unwrap_or(0),
+            height: y,
+            start,
+            end,
+        })
+    }
+
+    /// Gets the start point.
+    pub fn start(&self) -> Point2D {
+        self.start
+    }
+
+    /// Gets the end point.
+    pub fn end(&self) -> Point2D {
+        self.end
+    }
+
+    /// Gets the width of the heightmap.
+    pub fn width(&self) -> i64 {
+        self.width
+    }
+
+    /// Gets the height of the heightmap.
+    pub fn height(&self) -> i64 {
+        self.height
+    }
+
+    /// Gets the elevation at the given point, or None if the point is outside the heightmap.
+    pub fn elevation(&self, loc: &Point2D) -> Option<i64> {
+        self.elevations.get(loc).copied()
+    }
+
+    /// Gets the points that can be stepped to from the given point under the climb rule.
+    pub fn next_points(&self, loc: &Point2D, rule: &ClimbRule) -> Vec<Point2D> {
+        self.adjacent_points(loc)
+            .into_iter()
+            .filter(|next| rule.allows(self.elevations[loc], self.elevations[next]))
+            .collect()
+    }
+
+    /// Gets the points from which the given point can be stepped to under the climb rule.
+    pub fn previous_points(&self, loc: &Point2D, rule: &ClimbRule) -> Vec<Point2D> {
+        self.adjacent_points(loc)
+            .into_iter()
+            .filter(|prev| rule.allows(self.elevations[prev], self.elevations[loc]))
+            .collect()
+    }
+
+    /// Finds the shortest route from the first point to the second point under the climb rule,
+    /// using a breadth-first search. Returned value is the points along the route (including both
+    /// ends), or None if the second point cannot be reached.
+    pub fn find_route(
+        &self,
+        from: &Point2D,
+        to: &Point2D,
+        rule: &ClimbRule,
+    ) -> Option<Vec<Point2D>> {
+        if !self.elevations.contains_key(from) || !self.elevations.contains_key(to) {
+            return None;
+        }
+        let mut visit_queue: VecDeque<Point2D> = VecDeque::from([*from]);
+        let mut came_from: HashMap<Point2D, Point2D> = HashMap::new();
+        came_from.insert(*from, *from);
+        while let Some(loc) = visit_queue.pop_front() {
+            if loc == *to {
+                // Walk back along the route to the first point
+                let mut route = vec![loc];
+                let mut current = loc;
+                while current != *from {
+                    current = came_from[&current];
+                    route.push(current);
+                }
+                route.reverse();
+                return Some(route);
+            }
+            for next in self.next_points(&loc, rule) {
+                if let Entry::Vacant(e) = came_from.entry(next) {
+                    e.insert(loc);
+                    visit_queue.push_back(next);
+                }
+            }
+        }
+        None
+    }
+
+    /// Gets the adjacent points within the bounds of the heightmap.
+    fn adjacent_points(&self, loc: &Point2D) -> Vec<Point2D> {
+        loc.get_adjacent_points()
+            .into_iter()
+            .filter(|p| self.elevations.contains_key(p))
+            .collect()
+    }
+}
@@ -0,0 +1,5 @@
+mod heightmap;
+mod route;
+
+pub use self::heightmap::{ClimbRule, Heightmap};
+pub use self::route::{render_route, DistanceField};
@@ -0,0 +1,91 @@
+use std::collections::hash_map::Entry;
+use std::collections::{HashMap, VecDeque};
+
+use super::{ClimbRule, Heightmap};
+use crate::utils::cartography::Point2D;
+
+/// Minimum number of steps needed to reach a target point from every point of a heightmap that can
+/// reach it under a climb rule.
+#[derive(Clone, Debug)]
+pub struct DistanceField {
+    target: Point2D,
+    distances: HashMap<Point2D, u64>,
+    best_by_elevation: HashMap<i64, (Point2D, u64)>,
+}
+
+impl DistanceField {
+    /// Calculates the distance field for the target point, using a breadth-first search that works
+    /// backwards from the target.
+    pub fn new(heightmap: &Heightmap, target: &Point2D, rule: &ClimbRule) -> Self {
+        let mut distances: HashMap<Point2D, u64> = HashMap::new();
+        let mut best_by_elevation: HashMap<i64, (Point2D, u64)> = HashMap::new();
+        let mut visit_queue: VecDeque<Point2D> = VecDeque::new();
+        if heightmap.elevation(target).is_some() {
+            distances.insert(*target, 0);
+            visit_queue.push_back(*target);
+        }
+        while let Some(loc) = visit_queue.pop_front() {
+            let steps = distances[&loc];
+            // Points are visited in order of distance, so the first at each elevation is closest
+            best_by_elevation
+                .entry(heightmap.elevation(&loc).unwrap())
+                .or_insert((loc, steps));
+            for prev in heightmap.previous_points(&loc, rule) {
+                if let Entry::Vacant(e) = distances.entry(prev) {
+                    e.insert(steps + 1);
+                    visit_queue.push_back(prev);
+                }
+            }
+        }
+        Self {
+            target: *target,
+            distances,
+            best_by_elevation,
+        }
+    }
+
+    /// Gets the target point.
+    pub fn target(&self) -> Point2D {
+        self.target
+    }
+
+    /// Gets the minimum number of steps needed to reach the target from the given point, or None
+    /// if the target cannot be reached.
+    pub fn distance(&self, loc: &Point2D) -> Option<u64> {
+        self.distances.get(loc).copied()
+    }
+
+    /// Gets the point at the given elevation closest to the target and its distance, or None if
+    /// the target cannot be reached from any point at the elevation.
+    pub fn best_start(&self, elevation: i64) -> Option<(Point2D, u64)> {
+        self.best_by_elevation.get(&elevation).copied()
+    }
+}
+
+/// Renders the route over the heightmap in the puzzle format. Each point along the route is marked
+/// with an arrow (<, >, ^ or v) pointing to the next point, the last point is marked with E and all
+/// other points are marked with a dot.
+pub fn render_route(heightmap: &Heightmap, route: &[Point2D]) -> String {
+    let mut markers: HashMap<Point2D, char> = HashMap::new();
+    for step in route.windows(2) {
+        let marker = match (step[1].x() - step[0].x(), step[1].y() - step[0].y()) {
+            (1, 0) => '>',
+            (-1, 0) => '<',
+            (0, 1) => 'v',
+            (0, -1) => '^',
+            _ => '?',
+        };
+        markers.insert(step[0], marker);
+    }
+    if let Some(last) = route.last() {
+        markers.insert(*last, 'E');
+    }
+    let mut output = String::new();
+    for y in 0..heightmap.height() {
+        for x in 0..heightmap.width() {
+            output.push(*markers.get(&Point2D::new(x, y)).unwrap_or(&'.'));
+        }
+        output.push('\n');
+    }
+    output
+}
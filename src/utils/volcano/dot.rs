@@ -0,0 +1,209 @@
+use std::collections::{HashMap, HashSet};
+
+use super::{CompressedGraph, ValveNetwork, ValvePlan};
+
+/// Colours used to highlight the route of each agent in a plan, in agent order.
+const AGENT_COLOURS: [&str; 5] = ["red", "blue", "darkgreen", "orange", "purple"];
+
+/// Renders the tunnel network as a Graphviz DOT graph. Valves are labelled with their flow rates.
+/// If a plan is given, the start valve is marked and the tunnels taken and valves opened by each
+/// agent are highlighted in the agent's colour.
+pub fn render_tunnel_dot(network: &ValveNetwork, plan: Option<&ValvePlan>) -> String {
+    let mut highlights = Highlights::default();
+    if let Some(plan) = plan {
+        for (agent, schedule) in plan.agents().iter().enumerate() {
+            let mut location = plan.start();
+            for stop in schedule.stops() {
+                for next in stop.moves() {
+                    highlights.add_edge(location, next, agent);
+                    location = next;
+                }
+                highlights.add_valve(stop.valve(), agent);
+            }
+        }
+    }
+    let start = plan.map(|plan| plan.start());
+    let mut lines = vec![String::from("graph tunnels {")];
+    for valve in 0..network.valve_count() {
+        lines.push(node_line(
+            network.name(valve),
+            network.flow_rate(valve),
+            start,
+            &highlights,
+        ));
+    }
+    // Tunnels are listed from both ends, so only give each pair of valves one edge. One-way tunnels
+    // are drawn with an arrow.
+    let mut seen: HashSet<(usize, usize)> = HashSet::new();
+    for from in 0..network.valve_count() {
+        for to in network.tunnels(from) {
+            if seen.insert((from.min(*to), from.max(*to))) {
+                lines.push(edge_line(
+                    network.name(from),
+                    network.name(*to),
+                    None,
+                    !network.tunnels(*to).contains(&from),
+                    &highlights,
+                ));
+            }
+        }
+    }
+    lines.push(String::from("}"));
+    lines.join("\n") + "\n"
+}
+
+/// Renders the compressed graph of the valves worth opening as a Graphviz DOT graph. Valves are
+/// labelled with their flow rates, and edges with the minutes needed to travel to and open the
+/// valve at either end. If a plan is given, the valves opened by each agent (and the order they
+/// are opened in) are highlighted in the agent's colour.
+pub fn render_compressed_dot(
+    network: &ValveNetwork,
+    graph: &CompressedGraph,
+    plan: Option<&ValvePlan>,
+) -> String {
+    let mut highlights = Highlights::default();
+    if let Some(plan) = plan {
+        for (agent, schedule) in plan.agents().iter().enumerate() {
+            let mut location = plan.start();
+            for stop in schedule.stops() {
+                highlights.add_edge(location, stop.valve(), agent);
+                highlights.add_valve(stop.valve(), agent);
+                location = stop.valve();
+            }
+        }
+    }
+    let start_name = network.name(graph.start());
+    let mut lines = vec![String::from("graph compressed {")];
+    lines.push(node_line(
+        start_name,
+        network.flow_rate(graph.start()),
+        Some(start_name),
+        &highlights,
+    ));
+    for (pos, valve) in graph.valves().iter().enumerate() {
+        if *valve != graph.start() {
+            lines.push(node_line(
+                network.name(*valve),
+                graph.flow_rate(pos),
+                Some(start_name),
+                &highlights,
+            ));
+        }
+    }
+    for (pos, valve) in graph.valves().iter().enumerate() {
+        if *valve != graph.start() {
+            lines.push(edge_line(
+                start_name,
+                network.name(*valve),
+                Some(graph.travel_from_start(pos) + 1),
+                false,
+                &highlights,
+            ));
+        }
+    }
+    // Valves joined by one-way tunnels can have different costs in each direction (or no route in
+    // one direction), so those are drawn as separate edges with arrows
+    for from in 0..graph.valves().len() {
+        for to in from + 1..graph.valves().len() {
+            let (from_name, to_name) = (
+                network.name(graph.valves()[from]),
+                network.name(graph.valves()[to]),
+            );
+            match (graph.travel(from, to), graph.travel(to, from)) {
+                (Some(there), Some(back)) if there == back => {
+                    lines.push(edge_line(
+                        from_name,
+                        to_name,
+                        Some(there + 1),
+                        false,
+                        &highlights,
+                    ));
+                }
+                (there, back) => {
+                    if let Some(there) = there {
+                        let cost = Some(there + 1);
+                        lines.push(edge_line(from_name, to_name, cost, true, &highlights));
+                    }
+                    if let Some(back) = back {
+                        let cost = Some(back + 1);
+                        lines.push(edge_line(to_name, from_name, cost, true, &highlights));
+                    }
+                }
+            }
+        }
+    }
+    lines.push(String::from("}"));
+    lines.join("\n") + "\n"
+}
+
+/// Valves and edges to highlight, with the agent used for the colour. Where agents share a valve
+/// or an edge, the first agent is used.
+#[derive(Default)]
+struct Highlights {
+    valves: HashMap<String, usize>,
+    edges: HashMap<(String, String), usize>,
+}
+
+impl Highlights {
+    fn add_valve(&mut self, valve: &str, agent: usize) {
+        self.valves.entry(valve.to_string()).or_insert(agent);
+    }
+
+    fn add_edge(&mut self, from: &str, to: &str, agent: usize) {
+        self.edges.entry(edge_key(from, to)).or_insert(agent);
+    }
+}
+
+/// Gets the key for the edge between the two valves, which is the same in either direction.
+fn edge_key(from: &str, to: &str) -> (String, String) {
+    if from <= to {
+        (from.to_string(), to.to_string())
+    } else {
+        (to.to_string(), from.to_string())
+    }
+}
+
+/// Gets the highlight colour for the agent.
+fn agent_colour(agent: usize) -> &'static str {
+    AGENT_COLOURS[agent % AGENT_COLOURS.len()]
+}
+
+/// Formats the DOT statement for a valve node.
+fn node_line(name: &str, flow_rate: u64, start: Option<&str>, highlights: &Highlights) -> String {
+    let mut attrs = vec![format!("label=\"{}\\nflow {}\"", name, flow_rate)];
+    if start == Some(name) {
+        attrs.push(String::from("shape=doublecircle"));
+    } else {
+        attrs.push(String::from("shape=circle"));
+    }
+    if let Some(agent) = highlights.valves.get(name) {
+        attrs.push(format!("color={}, penwidth=2", agent_colour(*agent)));
+    }
+    format!("  \"{}\" [{}];", name, attrs.join(", "))
+}
+
+/// Formats the DOT statement for an edge, with an optional cost label. Directed edges are drawn
+/// with an arrow pointing from the first valve to the second valve.
+fn edge_line(
+    from: &str,
+    to: &str,
+    cost: Option<u64>,
+    directed: bool,
+    highlights: &Highlights,
+) -> String {
+    let mut attrs: Vec<String> = vec![];
+    if let Some(cost) = cost {
+        attrs.push(format!("label=\"{}\"", cost));
+    }
+    if directed {
+        attrs.push(String::from("dir=forward"));
+    }
+    if let Some(agent) = highlights.edges.get(&edge_key(from, to)) {
+        attrs.push(format!("color={}, penwidth=2", agent_colour(*agent)));
+    }
+    if attrs.is_empty() {
+        format!("  \"{}\" -- \"{}\";", from, to)
+    } else {
+        format!("  \"{}\" -- \"{}\" [{}];", from, to, attrs.join(", "))
+    }
+}
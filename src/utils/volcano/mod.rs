@@ -0,0 +1,9 @@
+mod dot;
+mod network;
+mod optimiser;
+mod plan;
+
+pub use self::dot::{render_compressed_dot, render_tunnel_dot};
+pub use self::network::{CompressedGraph, ValveNetwork};
+pub use self::optimiser::ValveOptimiser;
+pub use self::plan::{AgentSchedule, ValvePlan, ValveStop};
@@ -0,0 +1,218 @@
+use std::collections::{HashMap, VecDeque};
+
+use regex::Regex;
+
+/// Network of valves connected by tunnels. Valve names are interned, so valves are referred to by
+/// their index in the order they were given.
+#[derive(Clone, Debug)]
+pub struct ValveNetwork {
+    names: Vec<String>,
+    indices: HashMap<String, usize>,
+    flow_rates: Vec<u64>,
+    tunnels: Vec<Vec<usize>>,
+}
+
+impl ValveNetwork {
+    /// Parses the valve network from the scan output, with one valve per line (e.g. "Valve BB has
+    /// flow rate=13; tunnels lead to valves CC, AA"). Every tunnel must lead to a valve in the scan.
+    pub fn parse(s: &str) -> Result<ValveNetwork, String> {
+        let regex_line = Regex::new(
+            r"^Valve (\w+) has flow rate=(\d+); tunnels? leads? to valves? (\w+(?:, \w+)*)$",
+        )
+        .unwrap();
+        let mut names: Vec<String> = vec![];
+        let mut indices: HashMap<String, usize> = HashMap::new();
+        let mut flow_rates: Vec<u64> = vec![];
+        let mut raw_tunnels: Vec<(usize, Vec<String>)> = vec![];
+        for (i, line) in s.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() {
+                continue;
+            }
+            let caps = match regex_line.captures(line) {
+                Some(caps) => caps,
+                None => return Err(format!("line {}: bad valve scan \"{}\"", i + 1, line)),
+            };
+            let name = caps[1].to_string();
+            if indices.contains_key(&name) {
+                return Err(format!(
+                    "line {}: valve {} given more than once",
+                    i + 1,
+                    name
+                ));
+            }
+            let flow_rate = caps[2]
+                .parse::<u64>()
+                .map_err(|_| format!("line {}: bad flow rate", i + 1))?;
+            indices.insert(name.clone(), names.len());
+            names.push(name);
+            flow_rates.push(flow_rate);
+            raw_tunnels.push((i + 1, caps[3].split(", ").map(String::from).collect()));
+        }
+        let mut tunnels: Vec<Vec<usize>> = vec![];
+        for (line, targets) in raw_tunnels {
+            let mut valve_tunnels: Vec<usize> = vec![];
+            for target in targets {
+                match indices.get(&target) {
+                    Some(index) => valve_tunnels.push(*index),
+                    None => {
+                        return Err(format!(
+                            "line {}: tunnel leads to unknown valve {}",
+                            line, target
+                        ))
+                    }
+                }
+            }
+            tunnels.push(valve_tunnels);
+        }
+        Ok(ValveNetwork {
+            names,
+            indices,
+            flow_rates,
+            tunnels,
+        })
+    }
+
+    /// Gets the number of valves in the network.
+    pub fn valve_count(&self) -> usize {
+        self.names.len()
+    }
+
+    /// Gets the name of the valve with the given index.
+    pub fn name(&self, valve: usize) -> &str {
+        &self.names[valve]
+    }
+
+    /// Gets the index of the valve with the given name, or None if there is no such valve.
+    pub fn index_of(&self, name: &str) -> Option<usize> {
+        self.indices.get(name).copied()
+    }
+
+    /// Gets the flow rate of the valve with the given index.
+    pub fn flow_rate(&self, valve: usize) -> u64 {
+        self.flow_rates[valve]
+    }
+
+    /// Gets the indices of the valves that the tunnels from the given valve lead to.
+    pub fn tunnels(&self, valve: usize) -> &[usize] {
+        &self.tunnels[valve]
+    }
+
+    /// Gets the minimum number of minutes needed to move from the valve to every other valve,
+    /// indexed by valve. Valves that cannot be reached are given None.
+    pub fn travel_times(&self, from: usize) -> Vec<Option<u64>> {
+        let mut times: Vec<Option<u64>> = vec![None; self.valve_count()];
+        let mut visit_queue: VecDeque<usize> = VecDeque::from([from]);
+        times[from] = Some(0);
+        while let Some(valve) = visit_queue.pop_front() {
+            let steps = times[valve].unwrap();
+            for next in &self.tunnels[valve] {
+                if times[*next].is_none() {
+                    times[*next] = Some(steps + 1);
+                    visit_queue.push_back(*next);
+                }
+            }
+        }
+        times
+    }
+
+    /// Finds a shortest route through the tunnels from the first valve to the second valve.
+    /// Returned value is the valves along the route (excluding the first valve), or None if the
+    /// second valve cannot be reached.
+    pub fn shortest_route(&self, from: usize, to: usize) -> Option<Vec<usize>> {
+        let mut came_from: Vec<Option<usize>> = vec![None; self.valve_count()];
+        let mut visit_queue: VecDeque<usize> = VecDeque::from([from]);
+        came_from[from] = Some(from);
+        while let Some(valve) = visit_queue.pop_front() {
+            if valve == to {
+                let mut route: Vec<usize> = vec![];
+                let mut current = valve;
+                while current != from {
+                    route.push(current);
+                    current = came_from[current].unwrap();
+                }
+                route.reverse();
+                return Some(route);
+            }
+            for next in &self.tunnels[valve] {
+                if came_from[*next].is_none() {
+                    came_from[*next] = Some(valve);
+                    visit_queue.push_back(*next);
+                }
+            }
+        }
+        None
+    }
+
+    /// Compresses the network into a weighted graph between the start valve and the valves with a
+    /// non-zero flow rate that can be reached from it. Tunnels can be one-way, so some of these
+    /// valves might not be reachable from each other.
+    pub fn compress(&self, start: &str) -> Result<CompressedGraph, String> {
+        let start = match self.index_of(start) {
+            Some(start) => start,
+            None => return Err(format!("start valve {} not found", start)),
+        };
+        let (valves, from_start): (Vec<usize>, Vec<u64>) = self
+            .travel_times(start)
+            .into_iter()
+            .enumerate()
+            .filter(|(v, _)| self.flow_rates[*v] > 0)
+            .filter_map(|(v, time)| time.map(|time| (v, time)))
+            .unzip();
+        let travel = valves
+            .iter()
+            .map(|from| {
+                let times = self.travel_times(*from);
+                valves.iter().map(|to| times[*to]).collect()
+            })
+            .collect::<Vec<Vec<Option<u64>>>>();
+        Ok(CompressedGraph {
+            start,
+            from_start,
+            flow_rates: valves.iter().map(|v| self.flow_rates[*v]).collect(),
+            valves,
+            travel,
+        })
+    }
+}
+
+/// Weighted graph between the start valve and the valves worth opening (those with a non-zero flow
+/// rate). The valves worth opening are referred to by their position in the graph, which is also
+/// their bit in a mask of opened valves.
+#[derive(Clone, Debug)]
+pub struct CompressedGraph {
+    start: usize,
+    valves: Vec<usize>,
+    flow_rates: Vec<u64>,
+    from_start: Vec<u64>,
+    travel: Vec<Vec<Option<u64>>>,
+}
+
+impl CompressedGraph {
+    /// Gets the network index of the start valve.
+    pub fn start(&self) -> usize {
+        self.start
+    }
+
+    /// Gets the network indices of the valves worth opening.
+    pub fn valves(&self) -> &[usize] {
+        &self.valves
+    }
+
+    /// Gets the flow rate of the valve at the given position in the graph.
+    pub fn flow_rate(&self, pos: usize) -> u64 {
+        self.flow_rates[pos]
+    }
+
+    /// Gets the number of minutes needed to move from the start valve to the valve at the given
+    /// position in the graph.
+    pub fn travel_from_start(&self, to: usize) -> u64 {
+        self.from_start[to]
+    }
+
+    /// Gets the number of minutes needed to move between the valves at the given positions in the
+    /// graph, or None if the second valve cannot be reached from the first.
+    pub fn travel(&self, from: usize, to: usize) -> Option<u64> {
+        self.travel[from][to]
+    }
+}
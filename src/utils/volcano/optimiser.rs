@@ -0,0 +1,264 @@
+use std::collections::HashMap;
+
+use super::{AgentSchedule, CompressedGraph, ValveNetwork, ValvePlan, ValveStop};
+
+/// Maximum number of valves worth opening that the optimiser supports, since it holds a value for
+/// every subset of the valves.
+const MAX_VALVES: usize = 24;
+
+/// Maximum number of valves worth opening that the optimiser supports when three or more agents
+/// open valves, since each agent after the second is combined by checking every subset of every
+/// mask of valves (3^n steps).
+const MAX_MULTI_AGENT_VALVES: usize = 16;
+
+/// Finds the maximum pressure that can be released by one or more agents opening valves. The best
+/// pressure for each set of opened valves (as a bitmask) is found once, then the sets opened by the
+/// agents are combined, since agents working together never open the same valve.
+#[derive(Clone, Debug)]
+pub struct ValveOptimiser {
+    network: ValveNetwork,
+    graph: CompressedGraph,
+    minutes: u64,
+    // Best pressure released by a single agent opening a subset of each mask of valves, with the
+    // mask of valves actually opened to get it
+    best_by_mask: Vec<u64>,
+    best_source: Vec<usize>,
+    // Order of the valves opened to get the best pressure for each mask reached by the search
+    routes: HashMap<usize, Vec<usize>>,
+}
+
+impl ValveOptimiser {
+    /// Creates a new optimiser for agents starting at the given valve with the given number of
+    /// minutes available. Moving through a tunnel and opening a valve each take one minute.
+    pub fn new(network: &ValveNetwork, start: &str, minutes: u64) -> Result<Self, String> {
+        let graph = network.compress(start)?;
+        let valve_count = graph.valves().len();
+        if valve_count > MAX_VALVES {
+            return Err(format!(
+                "{} valves are worth opening but at most {} are supported",
+                valve_count, MAX_VALVES
+            ));
+        }
+        let mut best_by_mask = vec![0; 1 << valve_count];
+        let mut routes: HashMap<usize, Vec<usize>> = HashMap::new();
+        for pos in 0..valve_count {
+            let cost = graph.travel_from_start(pos) + 1;
+            if cost < minutes {
+                let mut route = vec![pos];
+                search(
+                    &graph,
+                    minutes - cost,
+                    1 << pos,
+                    0,
+                    &mut route,
+                    &mut best_by_mask,
+                    &mut routes,
+                );
+            }
+        }
+        // Extend the best pressure for each mask to cover every subset of the mask
+        let mut best_source = (0..best_by_mask.len()).collect::<Vec<usize>>();
+        for bit in 0..valve_count {
+            for mask in 0..best_by_mask.len() {
+                let without = mask ^ (1 << bit);
+                if mask & (1 << bit) != 0 && best_by_mask[without] > best_by_mask[mask] {
+                    best_by_mask[mask] = best_by_mask[without];
+                    best_source[mask] = best_source[without];
+                }
+            }
+        }
+        Ok(Self {
+            network: network.clone(),
+            graph,
+            minutes,
+            best_by_mask,
+            best_source,
+            routes,
+        })
+    }
+
+    /// Gets the compressed graph of the valves worth opening.
+    pub fn graph(&self) -> &CompressedGraph {
+        &self.graph
+    }
+
+    /// Gets the number of minutes available.
+    pub fn minutes(&self) -> u64 {
+        self.minutes
+    }
+
+    /// Calculates the maximum pressure that can be released by the given number of agents. Returns
+    /// an error if more than two agents have valves to open and there are too many valves worth
+    /// opening to split between them.
+    pub fn max_pressure(&self, agents: usize) -> Result<u64, String> {
+        Ok(self
+            .split_valves(agents)?
+            .iter()
+            .map(|mask| self.best_by_mask[*mask])
+            .sum())
+    }
+
+    /// Finds the plan for the given number of agents that releases the maximum pressure. Returns an
+    /// error if the valves cannot be split between the agents (as for max_pressure), or if the
+    /// tunnels between the valves in the plan cannot be followed.
+    pub fn plan(&self, agents: usize) -> Result<ValvePlan, String> {
+        let schedules = self
+            .split_valves(agents)?
+            .iter()
+            .map(|mask| match self.routes.get(&self.best_source[*mask]) {
+                Some(route) => self.schedule(route),
+                None => self.schedule(&[]),
+            })
+            .collect::<Result<Vec<AgentSchedule>, String>>()?;
+        Ok(ValvePlan::new(
+            self.network.name(self.graph.start()),
+            self.minutes,
+            schedules,
+        ))
+    }
+
+    /// Splits the valves between the agents to release the maximum pressure. Returned value is the
+    /// mask of valves available to each agent, or an error if more than two agents have valves to
+    /// open and there are more than MAX_MULTI_AGENT_VALVES valves worth opening.
+    fn split_valves(&self, agents: usize) -> Result<Vec<usize>, String> {
+        let valve_count = self.graph.valves().len();
+        // Agents beyond one per valve are left with nothing to open
+        let working = agents.min(valve_count);
+        if working == 0 {
+            return Ok(vec![0; agents]);
+        }
+        if working > 2 && valve_count > MAX_MULTI_AGENT_VALVES {
+            return Err(format!(
+                "{} valves are worth opening but at most {} are supported for {} agents",
+                valve_count, MAX_MULTI_AGENT_VALVES, agents
+            ));
+        }
+        let full = self.best_by_mask.len() - 1;
+        // Best pressure for each mask with one agent more than the previous layer, with the mask
+        // given to the added agent. The first agent uses the single agent pressures, and only the
+        // full mask is needed for the last agent.
+        let mut layers: Vec<(Vec<u64>, Vec<usize>)> = vec![];
+        for _ in 2..working {
+            let previous = layers
+                .last()
+                .map_or(&self.best_by_mask[..], |(best, _)| best);
+            let layer = (0..=full)
+                .map(|mask| self.best_split(mask, previous))
+                .unzip();
+            layers.push(layer);
+        }
+        let mut masks: Vec<usize> = vec![];
+        let mut mask = full;
+        if working > 1 {
+            let previous = layers
+                .last()
+                .map_or(&self.best_by_mask[..], |(best, _)| best);
+            let (_, sub) = self.best_split(full, previous);
+            masks.push(sub);
+            mask ^= sub;
+        }
+        for (_, split) in layers.iter().rev() {
+            masks.push(split[mask]);
+            mask ^= split[mask];
+        }
+        masks.push(mask);
+        masks.resize(agents, 0);
+        Ok(masks)
+    }
+
+    /// Finds the best split of the mask between one added agent and the agents giving the previous
+    /// best pressures. Returned value is the pressure and the mask given to the added agent.
+    fn best_split(&self, mask: usize, previous: &[u64]) -> (u64, usize) {
+        submasks(mask)
+            .map(|sub| (self.best_by_mask[sub] + previous[mask ^ sub], sub))
+            .max_by_key(|(pressure, _)| *pressure)
+            .unwrap()
+    }
+
+    /// Builds the schedule for an agent opening the valves at the given positions in order. Returns
+    /// an error if there is no route through the tunnels between consecutive valves.
+    fn schedule(&self, route: &[usize]) -> Result<AgentSchedule, String> {
+        let mut stops: Vec<ValveStop> = vec![];
+        let mut location = self.graph.start();
+        let mut minute = 0;
+        let mut cumulative_pressure = 0;
+        for pos in route {
+            let valve = self.graph.valves()[*pos];
+            let path = self.network.shortest_route(location, valve).ok_or(format!(
+                "no route through the tunnels from valve {} to valve {}",
+                self.network.name(location),
+                self.network.name(valve)
+            ))?;
+            minute += path.len() as u64;
+            let arrival_minute = minute;
+            minute += 1;
+            cumulative_pressure += self.graph.flow_rate(*pos) * (self.minutes - minute);
+            stops.push(ValveStop::new(
+                self.network.name(valve),
+                self.graph.flow_rate(*pos),
+                path.iter()
+                    .map(|v| self.network.name(*v).to_string())
+                    .collect(),
+                arrival_minute,
+                minute,
+                cumulative_pressure,
+            ));
+            location = valve;
+        }
+        Ok(AgentSchedule::new(stops))
+    }
+}
+
+/// Searches the valve orders possible after opening the last valve in the route, recording the best
+/// pressure released (and the route giving it) for each mask of opened valves. The pressure
+/// includes each opened valve's total release up to the end of the available time.
+fn search(
+    graph: &CompressedGraph,
+    minutes_left: u64,
+    mask: usize,
+    pressure: u64,
+    route: &mut Vec<usize>,
+    best_by_mask: &mut [u64],
+    routes: &mut HashMap<usize, Vec<usize>>,
+) {
+    let pos = *route.last().unwrap();
+    let pressure = pressure + graph.flow_rate(pos) * minutes_left;
+    if pressure > best_by_mask[mask] {
+        best_by_mask[mask] = pressure;
+        routes.insert(mask, route.clone());
+    }
+    for next in 0..graph.valves().len() {
+        // Valves that cannot be reached from the current valve are skipped
+        let cost = match graph.travel(pos, next) {
+            Some(travel) => travel + 1,
+            None => continue,
+        };
+        if mask & (1 << next) == 0 && cost < minutes_left {
+            route.push(next);
+            search(
+                graph,
+                minutes_left - cost,
+                mask | (1 << next),
+                pressure,
+                route,
+                best_by_mask,
+                routes,
+            );
+            route.pop();
+        }
+    }
+}
+
+/// Iterates over every subset of the mask, including the mask itself and the empty mask.
+fn submasks(mask: usize) -> impl Iterator<Item = usize> {
+    let mut next = Some(mask);
+    std::iter::from_fn(move || {
+        let current = next?;
+        next = if current == 0 {
+            None
+        } else {
+            Some((current - 1) & mask)
+        };
+        Some(current)
+    })
+}
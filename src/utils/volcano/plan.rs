@@ -0,0 +1,258 @@
+use crate::utils::json::JsonValue;
+
+/// A valve opened by an agent as part of a plan. Minutes are counted from 1, so the valve starts
+/// releasing pressure in the minute after it is opened.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct ValveStop {
+    valve: String,
+    flow_rate: u64,
+    moves: Vec<String>,
+    arrival_minute: u64,
+    open_minute: u64,
+    cumulative_pressure: u64,
+}
+
+impl ValveStop {
+    /// Creates a new stop for the named valve, reached by moving through the given valves.
+    pub fn new(
+        valve: &str,
+        flow_rate: u64,
+        moves: Vec<String>,
+        arrival_minute: u64,
+        open_minute: u64,
+        cumulative_pressure: u64,
+    ) -> ValveStop {
+        ValveStop {
+            valve: valve.to_string(),
+            flow_rate,
+            moves,
+            arrival_minute,
+            open_minute,
+            cumulative_pressure,
+        }
+    }
+
+    /// Gets the name of the valve opened.
+    pub fn valve(&self) -> &str {
+        &self.valve
+    }
+
+    /// Gets the flow rate of the valve opened.
+    pub fn flow_rate(&self) -> u64 {
+        self.flow_rate
+    }
+
+    /// Gets the names of the valves moved to (one per minute) to reach the valve from the previous
+    /// stop, ending with the valve itself.
+    pub fn moves(&self) -> &[String] {
+        &self.moves
+    }
+
+    /// Gets the minute in which the agent arrives at the valve, or 0 if the agent starts there.
+    pub fn arrival_minute(&self) -> u64 {
+        self.arrival_minute
+    }
+
+    /// Gets the minute in which the agent opens the valve.
+    pub fn open_minute(&self) -> u64 {
+        self.open_minute
+    }
+
+    /// Gets the total pressure released by the agent's valves up to and including this valve, over
+    /// the whole time available.
+    pub fn cumulative_pressure(&self) -> u64 {
+        self.cumulative_pressure
+    }
+}
+
+/// Ordered valves opened by a single agent.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct AgentSchedule {
+    stops: Vec<ValveStop>,
+}
+
+impl AgentSchedule {
+    /// Creates a new schedule from the valves opened by the agent, in the order they are opened.
+    pub fn new(stops: Vec<ValveStop>) -> AgentSchedule {
+        AgentSchedule { stops }
+    }
+
+    /// Gets the valves opened by the agent, in the order they are opened.
+    pub fn stops(&self) -> &[ValveStop] {
+        &self.stops
+    }
+
+    /// Gets the total pressure released by the valves opened by the agent.
+    pub fn pressure(&self) -> u64 {
+        self.stops.last().map_or(0, |stop| stop.cumulative_pressure)
+    }
+
+    /// Gets the action taken by the agent in each minute, given as the valve moved to (or opened)
+    /// and whether the valve is opened. The agent stays put once its last valve is opened.
+    fn actions(&self) -> Vec<(&str, bool)> {
+        let mut actions: Vec<(&str, bool)> = vec![];
+        for stop in &self.stops {
+            actions.extend(stop.moves.iter().map(|v| (v.as_str(), false)));
+            actions.push((stop.valve.as_str(), true));
+        }
+        actions
+    }
+}
+
+/// Plan for one or more agents opening valves, starting together with a fixed number of minutes
+/// available. The first agent is "you" and the others are elephants.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct ValvePlan {
+    start: String,
+    minutes: u64,
+    agents: Vec<AgentSchedule>,
+}
+
+impl ValvePlan {
+    /// Creates a new plan for the agents starting at the named valve with the given number of
+    /// minutes available.
+    pub fn new(start: &str, minutes: u64, agents: Vec<AgentSchedule>) -> ValvePlan {
+        ValvePlan {
+            start: start.to_string(),
+            minutes,
+            agents,
+        }
+    }
+
+    /// Gets the name of the valve that the agents start at.
+    pub fn start(&self) -> &str {
+        &self.start
+    }
+
+    /// Gets the number of minutes available.
+    pub fn minutes(&self) -> u64 {
+        self.minutes
+    }
+
+    /// Gets the schedule for each agent.
+    pub fn agents(&self) -> &[AgentSchedule] {
+        &self.agents
+    }
+
+    /// Gets the total pressure released by all of the agents.
+    pub fn total_pressure(&self) -> u64 {
+        self.agents.iter().map(|agent| agent.pressure()).sum()
+    }
+
+    /// Renders the plan as a minute-by-minute log, in the same style as the puzzle walkthrough.
+    pub fn render_log(&self) -> String {
+        let actions = self
+            .agents
+            .iter()
+            .map(|agent| agent.actions())
+            .collect::<Vec<Vec<(&str, bool)>>>();
+        let mut sections: Vec<String> = vec![];
+        for minute in 1..=self.minutes {
+            let mut lines = vec![format!("== Minute {} ==", minute)];
+            // Valves opened in earlier minutes are releasing pressure
+            let mut open = self
+                .agents
+                .iter()
+                .flat_map(|agent| agent.stops.iter())
+                .filter(|stop| stop.open_minute < minute)
+                .collect::<Vec<&ValveStop>>();
+            open.sort_by(|a, b| a.valve.cmp(&b.valve));
+            let releasing = open.iter().map(|stop| stop.flow_rate).sum::<u64>();
+            let names = open
+                .iter()
+                .map(|stop| stop.valve.as_str())
+                .collect::<Vec<&str>>();
+            lines.push(match names[..] {
+                [] => String::from("No valves are open."),
+                [name] => format!("Valve {} is open, releasing {} pressure.", name, releasing),
+                [first, second] => format!(
+                    "Valves {} and {} are open, releasing {} pressure.",
+                    first, second, releasing
+                ),
+                [ref init @ .., last] => format!(
+                    "Valves {}, and {} are open, releasing {} pressure.",
+                    init.join(", "),
+                    last,
+                    releasing
+                ),
+            });
+            for (agent, agent_actions) in actions.iter().enumerate() {
+                if let Some((valve, opens)) = agent_actions.get(minute as usize - 1) {
+                    let (subject, verb) = match (agent, opens) {
+                        (0, false) => (String::from("You"), "move to"),
+                        (0, true) => (String::from("You"), "open"),
+                        (_, false) => (agent_name(agent), "moves to"),
+                        (_, true) => (agent_name(agent), "opens"),
+                    };
+                    lines.push(format!("{} {} valve {}.", subject, verb, valve));
+                }
+            }
+            sections.push(lines.join("\n"));
+        }
+        let mut log = sections.join("\n\n");
+        log.push('\n');
+        log
+    }
+
+    /// Converts the plan into a JSON object.
+    pub fn to_json(&self) -> JsonValue {
+        let agents = self
+            .agents
+            .iter()
+            .enumerate()
+            .map(|(i, agent)| JsonValue::Object {
+                entries: vec![
+                    (String::from("agent"), JsonValue::from(i as u64)),
+                    (String::from("pressure"), JsonValue::from(agent.pressure())),
+                    (
+                        String::from("valves"),
+                        JsonValue::Array {
+                            items: agent.stops.iter().map(stop_to_json).collect(),
+                        },
+                    ),
+                ],
+            })
+            .collect();
+        JsonValue::Object {
+            entries: vec![
+                (String::from("start"), JsonValue::from(self.start.as_str())),
+                (String::from("minutes"), JsonValue::from(self.minutes)),
+                (
+                    String::from("total_pressure"),
+                    JsonValue::from(self.total_pressure()),
+                ),
+                (String::from("agents"), JsonValue::Array { items: agents }),
+            ],
+        }
+    }
+}
+
+/// Gets the name used for the agent at the given index (other than "you") in the log.
+fn agent_name(agent: usize) -> String {
+    match agent {
+        1 => String::from("The elephant"),
+        _ => format!("Elephant {}", agent),
+    }
+}
+
+/// Converts a valve stop into a JSON object.
+fn stop_to_json(stop: &ValveStop) -> JsonValue {
+    JsonValue::Object {
+        entries: vec![
+            (String::from("valve"), JsonValue::from(stop.valve.as_str())),
+            (String::from("flow_rate"), JsonValue::from(stop.flow_rate)),
+            (
+                String::from("arrival_minute"),
+                JsonValue::from(stop.arrival_minute),
+            ),
+            (
+                String::from("open_minute"),
+                JsonValue::from(stop.open_minute),
+            ),
+            (
+                String::from("cumulative_pressure"),
+                JsonValue::from(stop.cumulative_pressure),
+            ),
+        ],
+    }
+}
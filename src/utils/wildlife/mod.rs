@@ -1,3 +1,13 @@
 mod monkey;
+mod notebook;
+mod operation;
+mod projection;
+mod troop;
+mod worry;
 
-pub use self::monkey::{Monkey, Operation};
+pub use self::monkey::Monkey;
+pub use self::notebook::parse_monkeys;
+pub use self::operation::Operation;
+pub use self::projection::{project_inspections, MAX_EXACT_TRACE_ROUNDS};
+pub use self::troop::{BusinessMetric, MonkeyTroop, RoundSnapshot};
+pub use self::worry::WorryLevel;
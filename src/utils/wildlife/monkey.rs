@@ -1,9 +1,14 @@
 use std::collections::VecDeque;
+use std::fmt;
 
-/// Represents a single monkey.
-#[derive(Clone)]
-pub struct Monkey {
-    items: VecDeque<u64>,
+use super::{Operation, WorryLevel};
+use crate::utils::numeric::BigUint;
+
+/// Represents a single monkey. Item worry levels are held as u64 by default.
+#[derive(Clone, Debug)]
+pub struct Monkey<W: WorryLevel = u64> {
+    id: usize,
+    items: VecDeque<W>,
     op: Operation,
     divisor: u64,
     true_monkey: usize,
@@ -11,16 +16,18 @@ pub struct Monkey {
     items_inspected: u64,
 }
 
-impl Monkey {
+impl<W: WorryLevel> Monkey<W> {
     /// Creates a new monkey, with items inspected set to 0.
     pub fn new(
-        items: VecDeque<u64>,
+        id: usize,
+        items: VecDeque<W>,
         op: Operation,
         divisor: u64,
         true_monkey: usize,
         false_monkey: usize,
     ) -> Self {
         Self {
+            id,
             items,
             op,
             divisor,
@@ -31,10 +38,30 @@ impl Monkey {
     }
 
     /// Adds the item to the end of the monkey's current items.
-    pub fn give_item(&mut self, item: u64) {
+    pub fn give_item(&mut self, item: W) {
         self.items.push_back(item);
     }
 
+    /// Gets the id of the monkey.
+    pub fn get_id(&self) -> usize {
+        self.id
+    }
+
+    /// Gets the worry levels of the items currently held by the monkey, in inspection order.
+    pub fn get_items(&self) -> &VecDeque<W> {
+        &self.items
+    }
+
+    /// Gets the operation the monkey applies to item worry levels when inspecting them.
+    pub fn get_operation(&self) -> &Operation {
+        &self.op
+    }
+
+    /// Gets the ids of the monkeys that items are thrown to if the test passes and fails.
+    pub fn get_targets(&self) -> (usize, usize) {
+        (self.true_monkey, self.false_monkey)
+    }
+
     /// Gets the divisor of the monkey used to check item worry levels.
     pub fn get_divisor(&self) -> u64 {
         self.divisor
@@ -45,47 +72,69 @@ impl Monkey {
         self.items_inspected
     }
 
-    /// Monkey inspects and throws each of its items in order.
-    pub fn inspect_and_throw(&mut self, reduce_worry: bool, supermodulo: u64) -> Vec<(usize, u64)> {
-        let mut thrown_items: Vec<(usize, u64)> = vec![];
-        loop {
-            if self.items.is_empty() {
-                break;
-            }
-            // Inspect item
-            self.items_inspected += 1;
-            match self.op {
-                Operation::Add { value } => self.items[0] += value,
-                Operation::Mult { value } => self.items[0] *= value,
-                Operation::Pow { value } => self.items[0] = self.items[0].pow(value),
-            }
-            // Reduce the worry
-            if reduce_worry {
-                self.items[0] /= 3;
-            }
-            // Apply the supermodulo to reduce the item worry to stop it becoming too large
-            self.items[0] %= supermodulo;
-            if self.items[0] == 0 {
-                self.items[0] = supermodulo;
-            }
-            // Check for throw
-            let new_monkey = {
-                if self.items[0] % self.divisor == 0 {
-                    self.true_monkey
+    /// Monkey inspects and throws each of its items in order. Returned value is vector of the ids of
+    /// the receiving monkeys and the thrown items, or an error if a worry level cannot be
+    /// represented. Every item is inspected before any is thrown, so on an error the monkey keeps
+    /// all of its items and its count of items inspected is unchanged.
+    pub fn inspect_and_throw(
+        &mut self,
+        reduce_worry: bool,
+        supermodulo: u64,
+    ) -> Result<Vec<(usize, W)>, String> {
+        // Inspect items
+        let inspected_items = self
+            .items
+            .iter()
+            .map(|item| item.inspect(&self.op, reduce_worry, supermodulo))
+            .collect::<Result<Vec<W>, String>>()
+            .map_err(|e| format!("monkey {}: {}", self.id, e))?;
+        self.items.clear();
+        self.items_inspected += inspected_items.len() as u64;
+        // Check for throws
+        let thrown_items = inspected_items
+            .into_iter()
+            .map(|item| {
+                if item.is_divisible_by(self.divisor) {
+                    (self.true_monkey, item)
                 } else {
-                    self.false_monkey
+                    (self.false_monkey, item)
                 }
-            };
-            thrown_items.push((new_monkey, self.items.pop_front().unwrap()));
+            })
+            .collect();
+        Ok(thrown_items)
+    }
+}
+
+impl Monkey {
+    /// Creates a copy of the monkey that tracks the exact worry levels of its items using
+    /// arbitrary-precision arithmetic.
+    pub fn to_exact(&self) -> Monkey<BigUint> {
+        Monkey {
+            id: self.id,
+            items: self.items.iter().map(|item| BigUint::from(*item)).collect(),
+            op: self.op.clone(),
+            divisor: self.divisor,
+            true_monkey: self.true_monkey,
+            false_monkey: self.false_monkey,
+            items_inspected: self.items_inspected,
         }
-        thrown_items
     }
 }
 
-/// Represents an operator performed on the worry level of items by monkey.
-#[derive(Clone, Copy)]
-pub enum Operation {
-    Add { value: u64 },
-    Mult { value: u64 },
-    Pow { value: u32 }, // value is u32 here to allow use as exponent in .pow() method
+impl<W: WorryLevel> fmt::Display for Monkey<W> {
+    /// Displays the monkey in the notebook format used by the AOC 2022 Day 11 input, with the items
+    /// the monkey currently holds as its starting items.
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let items = self
+            .items
+            .iter()
+            .map(|item| item.to_string())
+            .collect::<Vec<String>>();
+        writeln!(f, "Monkey {}:", self.id)?;
+        writeln!(f, "  Starting items: {}", items.join(", "))?;
+        writeln!(f, "  Operation: new = {}", self.op)?;
+        writeln!(f, "  Test: divisible by {}", self.divisor)?;
+        writeln!(f, "    If true: throw to monkey {}", self.true_monkey)?;
+        write!(f, "    If false: throw to monkey {}", self.false_monkey)
+    }
 }
@@ -0,0 +1,92 @@
+use std::collections::VecDeque;
+
+use regex::Regex;
+
+use super::{Monkey, Operation};
+
+/// Parses the monkeys from notes in the format used by the AOC 2022 Day 11 input. Indentation,
+/// blank lines and line endings (LF or CRLF) are not significant. Returns an error if the notes are
+/// malformed, the monkey ids are not sequential from 0, a divisor is zero, or a monkey throws to
+/// itself or to a monkey that does not exist.
+pub fn parse_monkeys(notes: &str) -> Result<Vec<Monkey>, String> {
+    let regexes = [
+        Regex::new(r"^Monkey\s+(\d+)\s*:$").unwrap(),
+        Regex::new(r"^Starting items\s*:\s*(\d+(?:\s*,\s*\d+)*)?$").unwrap(),
+        Regex::new(r"^Operation\s*:\s*new\s*=\s*(.+)$").unwrap(),
+        Regex::new(r"^Test\s*:\s*divisible by\s+(\d+)$").unwrap(),
+        Regex::new(r"^If true\s*:\s*throw to monkey\s+(\d+)$").unwrap(),
+        Regex::new(r"^If false\s*:\s*throw to monkey\s+(\d+)$").unwrap(),
+    ];
+    let lines = notes
+        .lines()
+        .enumerate()
+        .map(|(i, line)| (i + 1, line.trim()))
+        .filter(|(_, line)| !line.is_empty())
+        .collect::<Vec<(usize, &str)>>();
+    let mut monkeys: Vec<Monkey> = vec![];
+    for chunk in lines.chunks(regexes.len()) {
+        // Match each line of the monkey's notes against the expected line format
+        let mut fields: Vec<&str> = vec![];
+        for (j, regex) in regexes.iter().enumerate() {
+            let (line_number, line) = match chunk.get(j) {
+                Some(line) => *line,
+                None => return Err(String::from("notes ended part way through a monkey")),
+            };
+            match regex.captures(line) {
+                Some(caps) => fields.push(caps.get(1).map_or("", |m| m.as_str())),
+                None => return Err(format!("line {}: unexpected \"{}\"", line_number, line)),
+            }
+        }
+        let line_number = chunk[0].0;
+        let id = fields[0].parse::<usize>().map_err(|e| e.to_string())?;
+        if id != monkeys.len() {
+            return Err(format!(
+                "line {}: expected monkey {}, found monkey {}",
+                line_number,
+                monkeys.len(),
+                id
+            ));
+        }
+        let items = fields[1]
+            .split(',')
+            .map(|item| item.trim())
+            .filter(|item| !item.is_empty())
+            .map(|item| item.parse::<u64>().map_err(|e| e.to_string()))
+            .collect::<Result<VecDeque<u64>, String>>()
+            .map_err(|e| format!("line {}: {}", chunk[1].0, e))?;
+        let op = fields[2]
+            .parse::<Operation>()
+            .map_err(|e| format!("line {}: {}", chunk[2].0, e))?;
+        let divisor = fields[3].parse::<u64>().map_err(|e| e.to_string())?;
+        if divisor == 0 {
+            return Err(format!("line {}: divisor must be non-zero", chunk[3].0));
+        }
+        let true_monkey = fields[4].parse::<usize>().map_err(|e| e.to_string())?;
+        let false_monkey = fields[5].parse::<usize>().map_err(|e| e.to_string())?;
+        monkeys.push(Monkey::new(
+            id,
+            items,
+            op,
+            divisor,
+            true_monkey,
+            false_monkey,
+        ));
+    }
+    // Throw targets can only be checked once the number of monkeys is known
+    for monkey in monkeys.iter() {
+        let (true_monkey, false_monkey) = monkey.get_targets();
+        for target in [true_monkey, false_monkey] {
+            if target == monkey.get_id() {
+                return Err(format!("monkey {} throws to itself", monkey.get_id()));
+            }
+            if target >= monkeys.len() {
+                return Err(format!(
+                    "monkey {} throws to monkey {}, which does not exist",
+                    monkey.get_id(),
+                    target
+                ));
+            }
+        }
+    }
+    Ok(monkeys)
+}
@@ -0,0 +1,283 @@
+use std::fmt;
+use std::str::FromStr;
+
+use crate::utils::numeric::BigUint;
+
+/// Represents the operation performed on the worry level of items by a monkey. Operations are
+/// expressions over the old worry level and non-negative integer literals.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub enum Operation {
+    Old,
+    Value {
+        value: u64,
+    },
+    Add {
+        left: Box<Operation>,
+        right: Box<Operation>,
+    },
+    Sub {
+        left: Box<Operation>,
+        right: Box<Operation>,
+    },
+    Mult {
+        left: Box<Operation>,
+        right: Box<Operation>,
+    },
+}
+
+impl Operation {
+    /// Evaluates the operation using exact arithmetic. Returns None if the result overflows or
+    /// would be negative.
+    pub fn evaluate(&self, old: u64) -> Option<u64> {
+        match self {
+            Operation::Old => Some(old),
+            Operation::Value { value } => Some(*value),
+            Operation::Add { left, right } => left.evaluate(old)?.checked_add(right.evaluate(old)?),
+            Operation::Sub { left, right } => left.evaluate(old)?.checked_sub(right.evaluate(old)?),
+            Operation::Mult { left, right } => {
+                left.evaluate(old)?.checked_mul(right.evaluate(old)?)
+            }
+        }
+    }
+
+    /// Evaluates the operation using arbitrary-precision arithmetic. Returns None if the result
+    /// would be negative.
+    pub fn evaluate_big(&self, old: &BigUint) -> Option<BigUint> {
+        match self {
+            Operation::Old => Some(old.clone()),
+            Operation::Value { value } => Some(BigUint::from(*value)),
+            Operation::Add { left, right } => {
+                Some(&left.evaluate_big(old)? + &right.evaluate_big(old)?)
+            }
+            Operation::Sub { left, right } => left
+                .evaluate_big(old)?
+                .checked_sub(&right.evaluate_big(old)?),
+            Operation::Mult { left, right } => {
+                Some(&left.evaluate_big(old)? * &right.evaluate_big(old)?)
+            }
+        }
+    }
+
+    /// Evaluates the operation using arithmetic modulo the given (non-zero) modulus. The result is
+    /// in the range 0 to modulus - 1, and is congruent to the exact result. Returns None if the
+    /// operation subtracts a value that could be larger than the value it is subtracted from, since
+    /// a negative exact result cannot be told apart from a positive one modulo the modulus.
+    pub fn evaluate_mod(&self, old: u64, modulus: u64) -> Option<u64> {
+        if !self.is_non_negative() {
+            return None;
+        }
+        Some(self.evaluate_mod_unchecked(old, modulus))
+    }
+
+    /// Evaluates the operation modulo the modulus, wrapping any negative result.
+    fn evaluate_mod_unchecked(&self, old: u64, modulus: u64) -> u64 {
+        let m = modulus as u128;
+        match self {
+            Operation::Old => old % modulus,
+            Operation::Value { value } => value % modulus,
+            Operation::Add { left, right } => {
+                let sum = left.evaluate_mod_unchecked(old, modulus) as u128
+                    + right.evaluate_mod_unchecked(old, modulus) as u128;
+                (sum % m) as u64
+            }
+            Operation::Sub { left, right } => {
+                let diff = left.evaluate_mod_unchecked(old, modulus) as u128 + m
+                    - right.evaluate_mod_unchecked(old, modulus) as u128;
+                (diff % m) as u64
+            }
+            Operation::Mult { left, right } => {
+                let product = left.evaluate_mod_unchecked(old, modulus) as u128
+                    * right.evaluate_mod_unchecked(old, modulus) as u128;
+                (product % m) as u64
+            }
+        }
+    }
+
+    /// Checks if every subtraction in the operation is non-negative for any old worry level, with
+    /// the smallest value subtracted from never less than the largest value subtracted.
+    fn is_non_negative(&self) -> bool {
+        match self {
+            Operation::Old | Operation::Value { .. } => true,
+            Operation::Add { left, right } | Operation::Mult { left, right } => {
+                left.is_non_negative() && right.is_non_negative()
+            }
+            Operation::Sub { left, right } => {
+                left.is_non_negative()
+                    && right.is_non_negative()
+                    && right.bounds().1.is_some_and(|max| left.bounds().0 >= max)
+            }
+        }
+    }
+
+    /// Gets the smallest and largest values of the operation over every old worry level, with no
+    /// largest value if the operation is unbounded. Only valid if the operation is non-negative.
+    fn bounds(&self) -> (u64, Option<u64>) {
+        match self {
+            Operation::Old => (0, None),
+            Operation::Value { value } => (*value, Some(*value)),
+            Operation::Add { left, right } => {
+                let ((left_min, left_max), (right_min, right_max)) =
+                    (left.bounds(), right.bounds());
+                (
+                    left_min.saturating_add(right_min),
+                    left_max.zip(right_max).and_then(|(a, b)| a.checked_add(b)),
+                )
+            }
+            Operation::Sub { left, right } => {
+                let ((left_min, left_max), (right_min, right_max)) =
+                    (left.bounds(), right.bounds());
+                (
+                    left_min.saturating_sub(right_max.unwrap_or(u64::MAX)),
+                    left_max.map(|a| a.saturating_sub(right_min)),
+                )
+            }
+            Operation::Mult { left, right } => {
+                let ((left_min, left_max), (right_min, right_max)) =
+                    (left.bounds(), right.bounds());
+                // A factor that is always zero bounds the product, however large the other factor
+                let max = match (left_max, right_max) {
+                    (Some(0), _) | (_, Some(0)) => Some(0),
+                    (Some(a), Some(b)) => a.checked_mul(b),
+                    _ => None,
+                };
+                (left_min.saturating_mul(right_min), max)
+            }
+        }
+    }
+
+    /// Gets the binding precedence of the operation, used when parsing and displaying it.
+    fn precedence(&self) -> u8 {
+        match self {
+            Operation::Add { .. } | Operation::Sub { .. } => 1,
+            Operation::Mult { .. } => 2,
+            Operation::Old | Operation::Value { .. } => 3,
+        }
+    }
+}
+
+impl fmt::Display for Operation {
+    /// Displays the operation in the notebook format (e.g. "old * 2 + 1"), with parentheses only
+    /// where needed for the expression to parse back to the same operation.
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let (left, symbol, right) = match self {
+            Operation::Old => return write!(f, "old"),
+            Operation::Value { value } => return write!(f, "{}", value),
+            Operation::Add { left, right } => (left, '+', right),
+            Operation::Sub { left, right } => (left, '-', right),
+            Operation::Mult { left, right } => (left, '*', right),
+        };
+        // Operators are left-associative, so the right operand needs parentheses at equal precedence
+        if left.precedence() < self.precedence() {
+            write!(f, "({})", left)?;
+        } else {
+            write!(f, "{}", left)?;
+        }
+        write!(f, " {} ", symbol)?;
+        if right.precedence() <= self.precedence() {
+            write!(f, "({})", right)
+        } else {
+            write!(f, "{}", right)
+        }
+    }
+}
+
+impl FromStr for Operation {
+    type Err = String;
+
+    /// Parses an operation from the expression on the right-hand side of "new = ". Expressions can
+    /// use "old", non-negative integers, +, -, * and parentheses, with * binding tighter than + and
+    /// -.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let tokens = tokenise(s)?;
+        let mut cursor = 0;
+        let op = parse_sum(&tokens, &mut cursor)?;
+        if cursor != tokens.len() {
+            return Err(format!(
+                "unexpected \"{}\" in operation \"{}\"",
+                tokens[cursor], s
+            ));
+        }
+        Ok(op)
+    }
+}
+
+/// Splits the expression into tokens - "old", integers and single-character symbols.
+fn tokenise(s: &str) -> Result<Vec<String>, String> {
+    let mut tokens: Vec<String> = vec![];
+    let chars = s.chars().collect::<Vec<char>>();
+    let mut i = 0;
+    while i < chars.len() {
+        let c = chars[i];
+        if c.is_whitespace() {
+            i += 1;
+        } else if "+-*()".contains(c) {
+            tokens.push(c.to_string());
+            i += 1;
+        } else if c.is_ascii_alphanumeric() {
+            let start = i;
+            while i < chars.len() && chars[i].is_ascii_alphanumeric() {
+                i += 1;
+            }
+            tokens.push(chars[start..i].iter().collect::<String>());
+        } else {
+            return Err(format!(
+                "unexpected character '{}' in operation \"{}\"",
+                c, s
+            ));
+        }
+    }
+    Ok(tokens)
+}
+
+/// Parses a sequence of terms joined by + and -.
+fn parse_sum(tokens: &[String], cursor: &mut usize) -> Result<Operation, String> {
+    let mut op = parse_product(tokens, cursor)?;
+    while *cursor < tokens.len() && (tokens[*cursor] == "+" || tokens[*cursor] == "-") {
+        let symbol = tokens[*cursor].clone();
+        *cursor += 1;
+        let left = Box::new(op);
+        let right = Box::new(parse_product(tokens, cursor)?);
+        op = if symbol == "+" {
+            Operation::Add { left, right }
+        } else {
+            Operation::Sub { left, right }
+        };
+    }
+    Ok(op)
+}
+
+/// Parses a sequence of factors joined by *.
+fn parse_product(tokens: &[String], cursor: &mut usize) -> Result<Operation, String> {
+    let mut op = parse_factor(tokens, cursor)?;
+    while *cursor < tokens.len() && tokens[*cursor] == "*" {
+        *cursor += 1;
+        let left = Box::new(op);
+        let right = Box::new(parse_factor(tokens, cursor)?);
+        op = Operation::Mult { left, right };
+    }
+    Ok(op)
+}
+
+/// Parses "old", an integer or a parenthesised expression.
+fn parse_factor(tokens: &[String], cursor: &mut usize) -> Result<Operation, String> {
+    let token = match tokens.get(*cursor) {
+        Some(token) => token,
+        None => return Err(String::from("operation ended unexpectedly")),
+    };
+    *cursor += 1;
+    if token == "old" {
+        return Ok(Operation::Old);
+    }
+    if token == "(" {
+        let op = parse_sum(tokens, cursor)?;
+        if tokens.get(*cursor).map(|t| t.as_str()) != Some(")") {
+            return Err(String::from("missing closing parenthesis in operation"));
+        }
+        *cursor += 1;
+        return Ok(op);
+    }
+    match token.parse::<u64>() {
+        Ok(value) => Ok(Operation::Value { value }),
+        Err(_) => Err(format!("unexpected \"{}\" in operation", token)),
+    }
+}
@@ -0,0 +1,136 @@
+use std::collections::HashMap;
+
+use super::{Monkey, WorryLevel};
+
+/// Maximum number of rounds an item is traced for with worry reduction in place, where its worry
+/// level is exact and is not guaranteed to return to a previously seen value.
+pub const MAX_EXACT_TRACE_ROUNDS: usize = 100_000;
+
+/// Projects the number of items each monkey will have inspected after the given number of rounds,
+/// indexed by monkey id. Each item is tracked independently of the others, since its path between
+/// the monkeys depends only on its own worry level. The state of an item at the start of each round
+/// (holding monkey and worry level) is recorded, and once a state repeats the inspections made over
+/// the detected cycle are extrapolated to the remaining rounds. Returns an error if a worry level
+/// cannot be represented before a cycle is found, or if the supermodulo of the monkey divisors
+/// overflows. With worry reduction in place the worry levels are exact and need not repeat, so
+/// each item is traced for at most MAX_EXACT_TRACE_ROUNDS rounds, and an error is returned if more
+/// rounds are requested but no cycle has been found by then.
+pub fn project_inspections(
+    monkeys: &[Monkey],
+    rounds: u128,
+    reduce_worry: bool,
+) -> Result<Vec<u128>, String> {
+    let divisors = monkeys
+        .iter()
+        .map(|m| m.get_divisor())
+        .collect::<Vec<u64>>();
+    let supermodulo = u64::supermodulo(&divisors, reduce_worry)?;
+    let mut inspections = vec![0u128; monkeys.len()];
+    for monkey in monkeys {
+        for item in monkey.get_items() {
+            let history = trace_item(
+                monkeys,
+                (monkey.get_id(), *item),
+                rounds,
+                reduce_worry,
+                supermodulo,
+            )?;
+            for (id, count) in history.project(rounds).into_iter().enumerate() {
+                inspections[id] += count;
+            }
+        }
+    }
+    Ok(inspections)
+}
+
+/// Record of the monkeys that inspected a single item in each round played, up to the first round
+/// where the item returned to a previously seen state.
+struct ItemHistory {
+    inspected_by: Vec<Vec<usize>>,
+    cycle_start: Option<usize>,
+    monkey_count: usize,
+}
+
+impl ItemHistory {
+    /// Calculates the number of times each monkey will have inspected the item after the given
+    /// number of rounds.
+    fn project(&self, rounds: u128) -> Vec<u128> {
+        let mut counts = vec![0u128; self.monkey_count];
+        let mut add_rounds = |from: usize, to: usize, multiplier: u128| {
+            for round in &self.inspected_by[from..to] {
+                for id in round {
+                    counts[*id] += multiplier;
+                }
+            }
+        };
+        let played = self.inspected_by.len();
+        match self.cycle_start {
+            Some(start) if rounds > played as u128 => {
+                let cycle_len = (played - start) as u128;
+                let remaining = rounds - start as u128;
+                add_rounds(0, start, 1);
+                add_rounds(start, played, remaining / cycle_len);
+                add_rounds(start, start + (remaining % cycle_len) as usize, 1);
+            }
+            _ => add_rounds(0, played.min(rounds as usize), 1),
+        }
+        counts
+    }
+}
+
+/// Plays the rounds for a single item starting in the given state, until the given number of rounds
+/// have been played or the item returns to a previously seen state.
+fn trace_item(
+    monkeys: &[Monkey],
+    initial: (usize, u64),
+    rounds: u128,
+    reduce_worry: bool,
+    supermodulo: u64,
+) -> Result<ItemHistory, String> {
+    let mut seen: HashMap<(usize, u64), usize> = HashMap::new();
+    let mut inspected_by: Vec<Vec<usize>> = vec![];
+    let (mut id, mut worry) = initial;
+    while (inspected_by.len() as u128) < rounds {
+        if reduce_worry && inspected_by.len() == MAX_EXACT_TRACE_ROUNDS {
+            return Err(format!(
+                "item starting with monkey {} and worry level {} did not cycle within {} rounds",
+                initial.0, initial.1, MAX_EXACT_TRACE_ROUNDS
+            ));
+        }
+        if let Some(&start) = seen.get(&(id, worry)) {
+            return Ok(ItemHistory {
+                inspected_by,
+                cycle_start: Some(start),
+                monkey_count: monkeys.len(),
+            });
+        }
+        seen.insert((id, worry), inspected_by.len());
+        // Monkeys take their turns in order, so an item thrown forward is inspected again in the
+        // same round
+        let mut round: Vec<usize> = vec![];
+        loop {
+            let monkey = &monkeys[id];
+            worry = worry
+                .inspect(monkey.get_operation(), reduce_worry, supermodulo)
+                .map_err(|e| format!("round {}: monkey {}: {}", inspected_by.len() + 1, id, e))?;
+            round.push(id);
+            let (true_monkey, false_monkey) = monkey.get_targets();
+            let target = if worry.is_divisible_by(monkey.get_divisor()) {
+                true_monkey
+            } else {
+                false_monkey
+            };
+            let thrown_forward = target > id;
+            id = target;
+            if !thrown_forward {
+                break;
+            }
+        }
+        inspected_by.push(round);
+    }
+    Ok(ItemHistory {
+        inspected_by,
+        cycle_start: None,
+        monkey_count: monkeys.len(),
+    })
+}
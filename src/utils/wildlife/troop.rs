@@ -0,0 +1,169 @@
+use super::{Monkey, WorryLevel};
+
+/// Simulates a troop of monkeys throwing items between themselves over a number of rounds. Item
+/// worry levels are held as u64 by default, or as BigUint to track exact worry levels.
+#[derive(Clone, Debug)]
+pub struct MonkeyTroop<W: WorryLevel = u64> {
+    monkeys: Vec<Monkey<W>>,
+    reduce_worry: bool,
+    supermodulo: u64,
+    round: usize,
+}
+
+impl<W: WorryLevel> MonkeyTroop<W> {
+    /// Creates a new troop from the initial state of the monkeys. If reduce_worry is set, item
+    /// worry levels are divided by three after each inspection. Returns an error if the worry
+    /// levels need to be kept modulo the monkey divisors but their supermodulo overflows.
+    pub fn new(monkeys: &[Monkey<W>], reduce_worry: bool) -> Result<Self, String> {
+        let divisors = monkeys
+            .iter()
+            .map(|m| m.get_divisor())
+            .collect::<Vec<u64>>();
+        let supermodulo = W::supermodulo(&divisors, reduce_worry)?;
+        Ok(Self {
+            monkeys: monkeys.to_vec(),
+            reduce_worry,
+            supermodulo,
+            round: 0,
+        })
+    }
+
+    /// Gets the current state of the monkeys.
+    pub fn monkeys(&self) -> &[Monkey<W>] {
+        &self.monkeys
+    }
+
+    /// Gets the number of rounds that have been played.
+    pub fn round(&self) -> usize {
+        self.round
+    }
+
+    /// Plays a single round, with each monkey inspecting and throwing its items in turn. Returned
+    /// value is vector of the throws made in the round, as (from, to, worry) tuples, or an error if
+    /// a worry level could not be represented. On an error the monkey that failed keeps all of its
+    /// items, so no items are lost, but the troop should not be played further since the round was
+    /// left incomplete.
+    pub fn play_round(&mut self) -> Result<Vec<(usize, usize, W)>, String> {
+        let mut throws: Vec<(usize, usize, W)> = vec![];
+        for i in 0..self.monkeys.len() {
+            // Get the items thrown by the current monkey then give them to the receiving monkey
+            let thrown_items = self.monkeys[i]
+                .inspect_and_throw(self.reduce_worry, self.supermodulo)
+                .map_err(|e| format!("round {}: {}", self.round + 1, e))?;
+            for (new_monkey, item) in thrown_items {
+                throws.push((i, new_monkey, item.clone()));
+                self.monkeys[new_monkey].give_item(item);
+            }
+        }
+        self.round += 1;
+        Ok(throws)
+    }
+
+    /// Plays the given number of rounds, yielding a snapshot of the troop after each round. The
+    /// iterator stops after yielding the first error.
+    pub fn rounds(
+        &mut self,
+        rounds: usize,
+    ) -> impl Iterator<Item = Result<RoundSnapshot<W>, String>> + '_ {
+        let mut failed = false;
+        (0..rounds).map_while(move |_| {
+            if failed {
+                return None;
+            }
+            let throws = match self.play_round() {
+                Ok(throws) => throws,
+                Err(e) => {
+                    failed = true;
+                    return Some(Err(e));
+                }
+            };
+            Some(Ok(RoundSnapshot {
+                round: self.round,
+                items: self
+                    .monkeys
+                    .iter()
+                    .map(|m| m.get_items().iter().cloned().collect())
+                    .collect(),
+                inspections: self.inspection_counts(),
+                throws,
+            }))
+        })
+    }
+
+    /// Gets the number of items each monkey has inspected, indexed by monkey id.
+    pub fn inspection_counts(&self) -> Vec<u64> {
+        self.monkeys
+            .iter()
+            .map(|m| m.get_items_inspected())
+            .collect()
+    }
+
+    /// Calculates the level of monkey business using the given metric. Returns None if the level
+    /// overflows u128.
+    pub fn monkey_business(&self, metric: BusinessMetric) -> Option<u128> {
+        let inspections = self
+            .inspection_counts()
+            .iter()
+            .map(|count| *count as u128)
+            .collect::<Vec<u128>>();
+        metric.calculate(&inspections)
+    }
+}
+
+/// Snapshot of the state of a monkey troop at the end of a round.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct RoundSnapshot<W: WorryLevel = u64> {
+    round: usize,
+    items: Vec<Vec<W>>,
+    inspections: Vec<u64>,
+    throws: Vec<(usize, usize, W)>,
+}
+
+impl<W: WorryLevel> RoundSnapshot<W> {
+    /// Gets the number of the round (starting from 1).
+    pub fn round(&self) -> usize {
+        self.round
+    }
+
+    /// Gets the worry levels of the items held by each monkey, indexed by monkey id.
+    pub fn items(&self) -> &[Vec<W>] {
+        &self.items
+    }
+
+    /// Gets the total number of items each monkey has inspected so far, indexed by monkey id.
+    pub fn inspections(&self) -> &[u64] {
+        &self.inspections
+    }
+
+    /// Gets the throws made during the round, in order, as (from, to, worry) tuples.
+    pub fn throws(&self) -> &[(usize, usize, W)] {
+        &self.throws
+    }
+}
+
+/// Metric used to calculate the level of monkey business from the number of items each monkey has
+/// inspected. Both variants use the k busiest monkeys.
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub enum BusinessMetric {
+    TopProduct { k: usize },
+    TopSum { k: usize },
+}
+
+impl BusinessMetric {
+    /// Calculates the level of monkey business from the inspection counts. Returns None if the level
+    /// overflows u128.
+    pub fn calculate(&self, inspections: &[u128]) -> Option<u128> {
+        let mut counts = inspections.to_vec();
+        counts.sort_unstable_by(|a, b| b.cmp(a));
+        match self {
+            BusinessMetric::TopProduct { k } => counts
+                .iter()
+                .take(*k)
+                .try_fold(1u128, |product, count| product.checked_mul(*count)),
+            BusinessMetric::TopSum { k } => counts
+                .iter()
+                .take(*k)
+                .try_fold(0u128, |sum, count| sum.checked_add(*count)),
+        }
+    }
+}
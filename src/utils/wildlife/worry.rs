@@ -0,0 +1,108 @@
+use std::fmt;
+
+use super::Operation;
+use crate::utils::numeric::BigUint;
+
+/// Represents the arithmetic used to track the worry level of an item held by a monkey.
+pub trait WorryLevel: Clone + fmt::Display + fmt::Debug {
+    /// Calculates the new worry level after a monkey inspects the item with the given operation.
+    /// If reduce_worry is set, the worry level is then divided by three. Returns an error if the
+    /// worry level cannot be represented.
+    fn inspect(&self, op: &Operation, reduce_worry: bool, supermodulo: u64)
+        -> Result<Self, String>;
+
+    /// Checks if the worry level is divisible by the given divisor.
+    fn is_divisible_by(&self, divisor: u64) -> bool;
+
+    /// Calculates the supermodulo passed to inspect from the divisors of the monkeys. Returns an
+    /// error if the supermodulo is needed but cannot be represented.
+    fn supermodulo(divisors: &[u64], reduce_worry: bool) -> Result<u64, String>;
+}
+
+/// Worry levels held as u64. Without worry reduction, levels are kept modulo the least common
+/// multiple of all the monkey divisors (the supermodulo), which preserves the result of every
+/// divisibility test. A negative level cannot be detected modulo the supermodulo, so unlike the
+/// exact modes an error is returned for any operation whose subtractions could go negative, even
+/// if the exact level would not. With worry reduction, levels are exact and an error is returned
+/// if they overflow or go negative.
+impl WorryLevel for u64 {
+    fn inspect(
+        &self,
+        op: &Operation,
+        reduce_worry: bool,
+        supermodulo: u64,
+    ) -> Result<Self, String> {
+        if reduce_worry {
+            match op.evaluate(*self) {
+                Some(worry) => Ok(worry / 3),
+                None => Err(format!(
+                    "worry level overflowed or went negative: new = {} with old = {}",
+                    op, self
+                )),
+            }
+        } else {
+            op.evaluate_mod(*self, supermodulo).ok_or(format!(
+                "worry level could go negative, which cannot be detected modulo {}: new = {}",
+                supermodulo, op
+            ))
+        }
+    }
+
+    fn is_divisible_by(&self, divisor: u64) -> bool {
+        self.is_multiple_of(divisor)
+    }
+
+    fn supermodulo(divisors: &[u64], reduce_worry: bool) -> Result<u64, String> {
+        // Exact worry levels are not kept modulo anything
+        if reduce_worry {
+            return Ok(1);
+        }
+        let mut supermodulo: u64 = 1;
+        for divisor in divisors {
+            supermodulo = (supermodulo / gcd(supermodulo, *divisor))
+                .checked_mul(*divisor)
+                .ok_or(format!(
+                    "least common multiple of the monkey divisors {:?} overflows u64",
+                    divisors
+                ))?;
+        }
+        Ok(supermodulo)
+    }
+}
+
+/// Worry levels held as arbitrary-precision integers, tracking the true worry level of each item.
+/// The supermodulo is not used.
+impl WorryLevel for BigUint {
+    fn inspect(
+        &self,
+        op: &Operation,
+        reduce_worry: bool,
+        _supermodulo: u64,
+    ) -> Result<Self, String> {
+        match op.evaluate_big(self) {
+            Some(worry) if reduce_worry => Ok(worry.div_rem_u64(3).0),
+            Some(worry) => Ok(worry),
+            None => Err(format!(
+                "worry level went negative: new = {} with old = {}",
+                op, self
+            )),
+        }
+    }
+
+    fn is_divisible_by(&self, divisor: u64) -> bool {
+        self.div_rem_u64(divisor).1 == 0
+    }
+
+    fn supermodulo(_divisors: &[u64], _reduce_worry: bool) -> Result<u64, String> {
+        Ok(1)
+    }
+}
+
+/// Calculates the greatest common divisor of the two values.
+fn gcd(a: u64, b: u64) -> u64 {
+    if b == 0 {
+        a
+    } else {
+        gcd(b, a % b)
+    }
+}